@@ -14,6 +14,11 @@ fn main() -> std::io::Result<()> {
 
     println!("Num decks: {num_decks} Max Pen: {max_penetration} \n\r\n");
     let mut table = Table::new(num_decks, 1, max_penetration);
+    table.subscribe(|event| {
+        if *event == shoo::Event::ShuffleStarted {
+            println!("\rShoe reshuffled!            ");
+        }
+    });
     let mut last_outcome = Some(Outcome::Push);
     loop {
         if let Event::Key(key_event) = read()? {
@@ -25,9 +30,7 @@ fn main() -> std::io::Result<()> {
                 KeyCode::Right => {
                     match last_outcome {
                         Some(_) => {
-                            if table.deal() {
-                                println!("\rShoe reshuffled!            ");
-                            }
+                            table.deal();
                             if table.peek() {
                                 print!("\rDealer has blackjack!            ");
                                 std::io::stdout().flush()?;
@@ -74,7 +77,7 @@ fn main() -> std::io::Result<()> {
                     }
 
                     table.flip_hole();
-                    while table.dealer_value().is_some_and(|v| v < 17) {
+                    while table.rules().dealer_hits(table.dealer_hand()) {
                         table.dealer_hit();
                         print!("\r{table}");
                         std::io::stdout().flush()?;
@@ -11,6 +11,7 @@ use std::{
 
 /// A chip to place a bet with
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Chip {
     /// A chip worth 1 unit
     One,
@@ -36,6 +37,7 @@ impl Chip {
 
 /// A bet of one or more chips
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bet {
     /// The chips in the bet
     chips: HashMap<Chip, usize>,
@@ -75,6 +77,25 @@ impl Bet {
     pub fn is_empty(&self) -> bool {
         self.units == 0
     }
+
+    /// Returns the total value of the bet in units
+    pub fn units(&self) -> usize {
+        self.units
+    }
+
+    /// Make a bet worth `units` from the fewest chips
+    pub fn from_units(units: usize) -> Self {
+        let mut chips = HashMap::new();
+        let mut remaining = units;
+        for chip in [Chip::Hundred, Chip::TwentyFive, Chip::Five, Chip::One] {
+            let count = remaining / chip.value();
+            if count > 0 {
+                chips.insert(chip, count);
+                remaining -= count * chip.value();
+            }
+        }
+        Self { chips, units }
+    }
 }
 
 impl IntoIterator for Bet {
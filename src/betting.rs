@@ -0,0 +1,183 @@
+/*!
+
+  Betting strategies
+
+*/
+
+use crate::bet::Bet;
+
+/// A strategy for sizing bets
+pub trait BettingStrategy {
+    /// Choose the bet for the next round at `true_count`, by the player's counting system,
+    /// given the player's `bankroll` in units. An empty bet sits the round out.
+    fn bet(&self, true_count: f32, bankroll: f64) -> Bet;
+}
+
+/// Bets the same number of units every round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flat {
+    /// The units bet every round
+    units: usize,
+}
+
+impl Flat {
+    /// Bet `units` every round
+    pub fn new(units: usize) -> Self {
+        Self { units }
+    }
+}
+
+impl Default for Flat {
+    /// Bet 1 unit every round
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl BettingStrategy for Flat {
+    fn bet(&self, _true_count: f32, _bankroll: f64) -> Bet {
+        Bet::from_units(self.units)
+    }
+}
+
+/// Bets more units as the true count rises, such as a 1-12 spread
+///
+/// ```
+/// use shoo::betting::TrueCountRamp;
+///
+/// // 1 unit up to a true count of 2, then 2, 4, 8 and 12 units
+/// let ramp = TrueCountRamp::new(1)
+///     .step(2.0, 2)
+///     .step(3.0, 4)
+///     .step(4.0, 8)
+///     .step(5.0, 12);
+/// assert_eq!(ramp.spread(), Some(12.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrueCountRamp {
+    /// The units bet below the first step
+    base: usize,
+    /// The true counts at which the bet changes and the units bet from them, by true count
+    steps: Vec<(f32, usize)>,
+}
+
+impl TrueCountRamp {
+    /// Bet `base` units at any true count below the first step
+    pub fn new(base: usize) -> Self {
+        Self {
+            base,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Bet `units` units from a true count of `true_count`, until the next step
+    pub fn step(mut self, true_count: f32, units: usize) -> Self {
+        let at = self.steps.partition_point(|&(tc, _)| tc < true_count);
+        self.steps.insert(at, (true_count, units));
+        self
+    }
+
+    /// Returns the units bet at `true_count`
+    pub fn units(&self, true_count: f32) -> usize {
+        self.steps
+            .iter()
+            .rev()
+            .find(|&&(tc, _)| true_count >= tc)
+            .map_or(self.base, |&(_, units)| units)
+    }
+
+    /// Returns the ratio of the largest bet to the smallest bet, or [None] if the ramp sits
+    /// some rounds out with a bet of 0 units
+    pub fn spread(&self) -> Option<f64> {
+        let units = self
+            .steps
+            .iter()
+            .map(|&(_, units)| units)
+            .chain([self.base]);
+        let (min, max) = units.fold((usize::MAX, 0), |(min, max), u| (min.min(u), max.max(u)));
+        (min > 0).then(|| max as f64 / min as f64)
+    }
+}
+
+impl BettingStrategy for TrueCountRamp {
+    fn bet(&self, true_count: f32, _bankroll: f64) -> Bet {
+        Bet::from_units(self.units(true_count))
+    }
+}
+
+/// Bets a fraction of the Kelly criterion: the bankroll times the advantage over the variance.
+///
+/// The advantage is estimated from the true count as a base advantage off the top of the shoe
+/// plus a fixed advantage per true count. Bets are rounded down to whole units and kept within
+/// the table limits, betting the minimum without an advantage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Kelly {
+    /// The fraction of the Kelly bet to make, 1.0 for full Kelly
+    fraction: f64,
+    /// The advantage at a true count of zero
+    base_advantage: f64,
+    /// The advantage gained per true count
+    advantage_per_count: f64,
+    /// The variance of the units won per unit bet
+    variance: f64,
+    /// The smallest bet in units
+    min_units: usize,
+    /// The largest bet in units
+    max_units: usize,
+}
+
+impl Kelly {
+    /// Bet `fraction` of the Kelly bet, 0.5 for half Kelly. The advantage defaults to -0.5% at a
+    /// true count of zero plus 0.5% per true count, the variance to 1.33 and the limits to
+    /// 1 to 1000 units.
+    pub fn new(fraction: f64) -> Self {
+        Self {
+            fraction,
+            base_advantage: -0.005,
+            advantage_per_count: 0.005,
+            variance: 1.33,
+            min_units: 1,
+            max_units: 1000,
+        }
+    }
+
+    /// Estimate the advantage as `base` at a true count of zero plus `per_count` per true count
+    pub fn advantage(mut self, base: f64, per_count: f64) -> Self {
+        self.base_advantage = base;
+        self.advantage_per_count = per_count;
+        self
+    }
+
+    /// Use `variance` as the variance of the units won per unit bet
+    pub fn variance(mut self, variance: f64) -> Self {
+        self.variance = variance;
+        self
+    }
+
+    /// Bet at least `min_units` and at most `max_units`
+    pub fn limits(mut self, min_units: usize, max_units: usize) -> Self {
+        self.min_units = min_units;
+        self.max_units = max_units.max(min_units);
+        self
+    }
+
+    /// Returns the estimated advantage at `true_count`
+    pub fn advantage_at(&self, true_count: f32) -> f64 {
+        self.base_advantage + self.advantage_per_count * true_count as f64
+    }
+
+    /// Returns the units bet at `true_count` with a bankroll of `bankroll` units
+    pub fn units(&self, true_count: f32, bankroll: f64) -> usize {
+        let kelly = self.fraction * bankroll * self.advantage_at(true_count) / self.variance;
+        (kelly.max(0.0).floor() as usize).clamp(self.min_units, self.max_units)
+    }
+}
+
+impl BettingStrategy for Kelly {
+    fn bet(&self, true_count: f32, bankroll: f64) -> Bet {
+        Bet::from_units(self.units(true_count, bankroll))
+    }
+}
@@ -4,10 +4,53 @@
 
 */
 
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
+
+/// An error parsing a [Card], [Rank], [Suit] or [Hand](crate::Hand)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    /// There was no card to parse
+    Empty,
+    /// The card is missing its rank or suit
+    Incomplete(String),
+    /// The rank is not recognized
+    InvalidRank(String),
+    /// The suit is not recognized
+    InvalidSuit(String),
+}
+
+impl Display for ParseCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "expected a card"),
+            Self::Incomplete(c) => write!(f, "card `{c}` needs a rank and a suit"),
+            Self::InvalidRank(r) => write!(f, "invalid rank `{r}`, expected A, 2-10, T, J, Q or K"),
+            Self::InvalidSuit(s) => write!(
+                f,
+                "invalid suit `{s}`, expected s, h, d, c or a suit symbol"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// The ASCII letter of each rank, in the order of [Rank::ALL], with tens as `T`
+const RANK_LETTERS: [char; 13] = [
+    'A', '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K',
+];
+
+/// The ASCII letter, outline symbol and filled symbol of each suit, in the order of [Suit::ALL]
+const SUIT_NOTATION: [(char, char, char); 4] = [
+    ('h', '♡', '♥'),
+    ('d', '♢', '♦'),
+    ('c', '♧', '♣'),
+    ('s', '♤', '♠'),
+];
 
 /// Suit of a card
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     /// Heart
     Heart,
@@ -21,17 +64,58 @@ pub enum Suit {
 
 impl Display for Suit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Heart => write!(f, "♡"),
-            Self::Diamond => write!(f, "♢"),
-            Self::Club => write!(f, "♧"),
-            Self::Spade => write!(f, "♤"),
-        }
+        write!(f, "{}", self.notation().1)
+    }
+}
+
+impl Suit {
+    /// Every suit
+    pub const ALL: [Suit; 4] = [Suit::Heart, Suit::Diamond, Suit::Club, Suit::Spade];
+
+    /// An iterator over every suit
+    pub fn iter() -> impl Iterator<Item = Suit> {
+        Self::ALL.into_iter()
+    }
+
+    /// Returns the letter, outline symbol and filled symbol of the suit
+    fn notation(self) -> (char, char, char) {
+        SUIT_NOTATION[self as usize]
+    }
+
+    /// Returns the lowercase letter of the suit, such as `s`
+    pub fn letter(self) -> char {
+        self.notation().0
+    }
+
+    /// Returns the filled symbol of the suit, such as `♠`
+    pub fn filled(self) -> char {
+        self.notation().2
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    /// Parses a suit letter in either case (`s`, `H`) or a filled or outline suit symbol
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let c = match (chars.next(), chars.next()) {
+            (None, _) => return Err(ParseCardError::Empty),
+            (Some(c), None) => c.to_ascii_lowercase(),
+            _ => return Err(ParseCardError::InvalidSuit(s.to_string())),
+        };
+        Self::iter()
+            .find(|suit| {
+                let (letter, outline, filled) = suit.notation();
+                [letter, outline, filled].contains(&c)
+            })
+            .ok_or_else(|| ParseCardError::InvalidSuit(s.to_string()))
     }
 }
 
 /// Rank of a card
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rank {
     /// 1/11
     Ace,
@@ -64,25 +148,90 @@ pub enum Rank {
 impl Display for Rank {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Ace => write!(f, "A"),
-            Self::Two => write!(f, "2"),
-            Self::Three => write!(f, "3"),
-            Self::Four => write!(f, "4"),
-            Self::Five => write!(f, "5"),
-            Self::Six => write!(f, "6"),
-            Self::Seven => write!(f, "7"),
-            Self::Eight => write!(f, "8"),
-            Self::Nine => write!(f, "9"),
             Self::Ten => write!(f, "10"),
-            Self::Jack => write!(f, "J"),
-            Self::Queen => write!(f, "Q"),
-            Self::King => write!(f, "K"),
+            _ => write!(f, "{}", self.letter()),
         }
     }
 }
 
+impl Rank {
+    /// Every rank, from ace to king
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
+    /// An iterator over every rank, from ace to king
+    pub fn iter() -> impl Iterator<Item = Rank> {
+        Self::ALL.into_iter()
+    }
+
+    /// Returns the single character of the rank, with tens as `T`
+    pub fn letter(self) -> char {
+        RANK_LETTERS[self as usize]
+    }
+
+    /// The ranks worth 10
+    pub fn ten_value() -> &'static [Rank] {
+        &[Rank::Ten, Rank::Jack, Rank::Queen, Rank::King]
+    }
+
+    /// Returns true if the rank is worth 10
+    pub fn is_ten_value(self) -> bool {
+        Self::ten_value().contains(&self)
+    }
+
+    /// Returns the value of the rank, counting an ace as 1
+    pub fn value(self) -> u8 {
+        match self {
+            Rank::Ace => 1,
+            Rank::Two => 2,
+            Rank::Three => 3,
+            Rank::Four => 4,
+            Rank::Five => 5,
+            Rank::Six => 6,
+            Rank::Seven => 7,
+            Rank::Eight => 8,
+            Rank::Nine => 9,
+            Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 10,
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    /// Parses a rank in either case, with tens written as `10` or `T`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseCardError::Empty);
+        }
+        if s == "10" {
+            return Ok(Self::Ten);
+        }
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::iter().find(|rank| rank.letter() == c.to_ascii_uppercase()),
+            _ => None,
+        }
+        .ok_or_else(|| ParseCardError::InvalidRank(s.to_string()))
+    }
+}
+
 /// A playing card
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     suit: Suit,
     rank: Rank,
@@ -94,6 +243,23 @@ impl Display for Card {
     }
 }
 
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    /// Parses a rank followed by a suit, such as `As`, `10h`, `Td`, `A♠` or `KD`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (rank, suit) = match s.char_indices().next_back() {
+            Some((i, _)) => s.split_at(i),
+            None => return Err(ParseCardError::Empty),
+        };
+        if rank.is_empty() {
+            return Err(ParseCardError::Incomplete(s.to_string()));
+        }
+        Ok(Self::new(suit.parse()?, rank.parse()?))
+    }
+}
+
 impl Card {
     /// Create a new card
     pub fn new(suit: Suit, rank: Rank) -> Self {
@@ -135,6 +301,11 @@ impl Card {
         }
     }
 
+    /// An iterator over the 52 cards of a standard deck, by suit and then by rank
+    pub fn iter() -> impl Iterator<Item = Card> {
+        Suit::iter().flat_map(|suit| Rank::iter().map(move |rank| Card::new(suit, rank)))
+    }
+
     /// Returns true if card is an ace
     pub fn is_ace(&self) -> bool {
         self.rank == Rank::Ace
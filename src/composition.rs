@@ -0,0 +1,138 @@
+/*!
+
+  Compact counts of cards by value
+
+*/
+
+use crate::{
+    card::{Card, Rank},
+    deck::Deck,
+    hand::Hand,
+    shoe::Shoe,
+};
+
+/// Returns the ranks of a composition, one per value: the ace, 2 through 9, and ten-valued
+/// cards as [Rank::Ten]
+pub(crate) fn ranks() -> impl Iterator<Item = Rank> {
+    Rank::iter().filter(|&rank| rank == Rank::Ten || !rank.is_ten_value())
+}
+
+/// The number of cards of each value in a set of cards, such as the cards remaining in a shoe.
+///
+/// Ten-valued ranks share a single count under [Rank::Ten], since they play the same.
+/// A composition is small, [Copy] and [Hash], so it can be used as a cache key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Composition {
+    /// The number of cards of each value, aces first and ten-valued cards last
+    counts: [u16; 10],
+    /// The total number of cards
+    total: u16,
+}
+
+/// Returns the index of the count of `rank`, in the order of [ranks]
+pub(crate) fn index(rank: Rank) -> usize {
+    rank.value() as usize - 1
+}
+
+impl Composition {
+    /// Create an empty composition
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the composition of `decks` standard 52-card decks
+    pub fn decks(decks: usize) -> Self {
+        let mut composition = Self::new();
+        for rank in Rank::iter() {
+            composition.add(rank, 4 * decks);
+        }
+        composition
+    }
+
+    /// Returns the number of cards of the same value as `rank`
+    pub fn count(&self, rank: Rank) -> usize {
+        self.counts[index(rank)] as usize
+    }
+
+    /// Returns the total number of cards
+    pub fn len(&self) -> usize {
+        self.total as usize
+    }
+
+    /// Returns true if there are no cards
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Add `count` cards of rank `rank`
+    ///
+    /// # Panics
+    /// Panics if the composition would hold more than 65535 cards
+    pub fn add(&mut self, rank: Rank, count: usize) {
+        self.total = u16::try_from(count)
+            .ok()
+            .and_then(|count| self.total.checked_add(count))
+            .expect("composition holds at most 65535 cards");
+        self.counts[index(rank)] += count as u16;
+    }
+
+    /// Remove a card of the same value as `rank`. Returns false if there is none.
+    pub fn remove(&mut self, rank: Rank) -> bool {
+        let count = &mut self.counts[index(rank)];
+        if *count == 0 {
+            return false;
+        }
+        *count -= 1;
+        self.total -= 1;
+        true
+    }
+
+    /// Returns the composition with a card of the same value as `rank` removed, if there is one
+    pub fn without(mut self, rank: Rank) -> Option<Self> {
+        self.remove(rank).then_some(self)
+    }
+
+    /// Returns the probability that a card drawn at random has the same value as `rank`
+    pub fn probability(&self, rank: Rank) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.count(rank) as f64 / self.total as f64
+        }
+    }
+
+    /// An iterator over the count of each value, as the ace, 2 through 9 and [Rank::Ten]
+    pub fn iter(&self) -> impl Iterator<Item = (Rank, usize)> + '_ {
+        ranks().map(|rank| (rank, self.count(rank)))
+    }
+}
+
+impl FromIterator<Card> for Composition {
+    fn from_iter<T: IntoIterator<Item = Card>>(iter: T) -> Self {
+        let mut composition = Self::new();
+        for card in iter {
+            composition.add(card.rank(), 1);
+        }
+        composition
+    }
+}
+
+impl From<&Shoe> for Composition {
+    /// The composition of the cards remaining in the shoe
+    fn from(shoe: &Shoe) -> Self {
+        shoe.upcoming().collect()
+    }
+}
+
+impl From<&Hand> for Composition {
+    fn from(hand: &Hand) -> Self {
+        hand.cards().iter().copied().collect()
+    }
+}
+
+impl From<&Deck> for Composition {
+    fn from(deck: &Deck) -> Self {
+        deck.iter().copied().collect()
+    }
+}
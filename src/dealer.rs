@@ -0,0 +1,261 @@
+/*!
+
+  Dealer outcome probabilities
+
+  [DealerOdds] holds the exact probability of each final dealer outcome for an upcard,
+  found by drawing every possible sequence of cards from a [Composition] without
+  replacement until the dealer stands or busts.
+
+*/
+
+use crate::{card::Rank, composition::Composition, table::TableRules};
+use std::{collections::HashMap, fmt::Display};
+
+/// A final dealer outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DealerOutcome {
+    /// A total of 17
+    Seventeen,
+    /// A total of 18
+    Eighteen,
+    /// A total of 19
+    Nineteen,
+    /// A total of 20
+    Twenty,
+    /// A total of 21 in three or more cards
+    TwentyOne,
+    /// A two-card 21
+    Blackjack,
+    /// Over 21
+    Bust,
+}
+
+impl DealerOutcome {
+    /// Every outcome, from 17 to bust
+    pub const ALL: [DealerOutcome; 7] = [
+        DealerOutcome::Seventeen,
+        DealerOutcome::Eighteen,
+        DealerOutcome::Nineteen,
+        DealerOutcome::Twenty,
+        DealerOutcome::TwentyOne,
+        DealerOutcome::Blackjack,
+        DealerOutcome::Bust,
+    ];
+
+    /// An iterator over every outcome, from 17 to bust
+    pub fn iter() -> impl Iterator<Item = DealerOutcome> {
+        Self::ALL.into_iter()
+    }
+
+    /// Returns the dealer's total, or `None` for a bust
+    pub fn total(self) -> Option<u8> {
+        match self {
+            Self::Seventeen => Some(17),
+            Self::Eighteen => Some(18),
+            Self::Nineteen => Some(19),
+            Self::Twenty => Some(20),
+            Self::TwentyOne | Self::Blackjack => Some(21),
+            Self::Bust => None,
+        }
+    }
+
+    /// Returns the outcome of a dealer standing on `total` in three or more cards
+    fn standing(total: u8) -> Self {
+        match total {
+            17 => Self::Seventeen,
+            18 => Self::Eighteen,
+            19 => Self::Nineteen,
+            20 => Self::Twenty,
+            21 => Self::TwentyOne,
+            _ => Self::Bust,
+        }
+    }
+}
+
+impl Display for DealerOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Blackjack => write!(f, "Blackjack"),
+            Self::Bust => write!(f, "Bust"),
+            _ => write!(f, "{}", self.total().unwrap_or_default()),
+        }
+    }
+}
+
+/// The probability of each final dealer outcome for an upcard
+///
+/// ```
+/// use shoo::{Composition, Rank, dealer::{DealerOdds, DealerOutcome}, table::TableRules};
+///
+/// let mut unseen = Composition::decks(6);
+/// unseen.remove(Rank::Six);
+/// let odds = DealerOdds::new(Rank::Six, &unseen, TableRules::default());
+/// assert!((odds.bust() - 0.42).abs() < 0.01);
+/// assert_eq!(odds.probability(DealerOutcome::Blackjack), 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DealerOdds {
+    /// The probability of each outcome, in the order of [DealerOutcome::ALL]
+    probabilities: [f64; 7],
+}
+
+impl DealerOdds {
+    /// Find the odds of the dealer showing `upcard`, drawing from `unseen`: the cards not yet
+    /// seen, without the upcard or any player cards. Only whether the dealer hits soft 17 and
+    /// peeks are taken from `rules`. With a peek under an ace or ten, the odds are given the
+    /// dealer does not have blackjack.
+    ///
+    /// Hands that run out of cards before the dealer finishes are left out, so the
+    /// probabilities only sum to less than one for compositions too small to finish a hand.
+    pub fn new(upcard: Rank, unseen: &Composition, rules: TableRules) -> Self {
+        DealerHands::new(upcard, rules.dealer_hits_soft_17).odds(unseen, rules.peek)
+    }
+
+    /// Returns the probability of `outcome`
+    pub fn probability(&self, outcome: DealerOutcome) -> f64 {
+        self.probabilities[outcome as usize]
+    }
+
+    /// Returns the probability that the dealer busts
+    pub fn bust(&self) -> f64 {
+        self.probability(DealerOutcome::Bust)
+    }
+
+    /// Returns the probability that the dealer has blackjack
+    pub fn blackjack(&self) -> f64 {
+        self.probability(DealerOutcome::Blackjack)
+    }
+
+    /// An iterator over the probability of each outcome, from 17 to bust
+    pub fn iter(&self) -> impl Iterator<Item = (DealerOutcome, f64)> + '_ {
+        DealerOutcome::iter().map(|outcome| (outcome, self.probability(outcome)))
+    }
+}
+
+/// A set of cards a dealer can draw to an upcard, from the hole card on, and the outcome
+#[derive(Debug, Clone)]
+struct DrawnHand {
+    /// The number of cards drawn of each value, by the position of the value in
+    /// [Composition::iter]
+    counts: Vec<(usize, usize)>,
+    /// The number of cards drawn
+    len: usize,
+    /// The outcome of the hand
+    outcome: DealerOutcome,
+    /// The number of orders the cards can be drawn in without the dealer finishing early
+    orders: f64,
+}
+
+/// Every set of cards a dealer can draw to an upcard.
+///
+/// The chance of drawing a set of cards in any one order only depends on how many of each
+/// value there are, so the odds for a composition are the sum over the sets of the number
+/// of orders the set can be drawn in times the chance of drawing it in one order. The sets are
+/// found once, to find the odds for many compositions quickly.
+#[derive(Debug, Clone)]
+pub(crate) struct DealerHands {
+    /// The sets of cards the dealer can draw
+    hands: Vec<DrawnHand>,
+}
+
+impl DealerHands {
+    /// Find every set of cards a dealer showing `upcard` can draw, hitting soft 17 if
+    /// `hits_soft_17`
+    pub(crate) fn new(upcard: Rank, hits_soft_17: bool) -> Self {
+        let mut sets = HashMap::new();
+        Self::draw(
+            &mut [0; 10],
+            upcard.value(),
+            upcard == Rank::Ace,
+            hits_soft_17,
+            &mut sets,
+        );
+        let hands = sets
+            .into_iter()
+            .map(|(counts, (outcome, orders))| DrawnHand {
+                counts: (0..10)
+                    .filter(|&value| counts[value] > 0)
+                    .map(|value| (value, counts[value] as usize))
+                    .collect(),
+                len: counts.iter().map(|&count| count as usize).sum(),
+                outcome,
+                orders,
+            })
+            .collect();
+        Self { hands }
+    }
+
+    /// Draw every card to a dealer holding a hard `total` and `ace` after drawing `counts` of
+    /// each value, counting the orders of each finished set in `sets`
+    fn draw(
+        counts: &mut [u8; 10],
+        total: u8,
+        ace: bool,
+        hits_soft_17: bool,
+        sets: &mut HashMap<[u8; 10], (DealerOutcome, f64)>,
+    ) {
+        let soft = ace && total <= 11;
+        let best = if soft { total + 10 } else { total };
+        let drawn = counts.iter().sum::<u8>();
+        let outcome = if drawn == 1 && best == 21 {
+            Some(DealerOutcome::Blackjack)
+        } else if best > 17 || (best == 17 && !(soft && hits_soft_17)) {
+            Some(DealerOutcome::standing(best))
+        } else {
+            None
+        };
+        if let Some(outcome) = outcome {
+            sets.entry(*counts).or_insert((outcome, 0.0)).1 += 1.0;
+            return;
+        }
+        for value in 1..=10 {
+            counts[value as usize - 1] += 1;
+            Self::draw(counts, total + value, ace || value == 1, hits_soft_17, sets);
+            counts[value as usize - 1] -= 1;
+        }
+    }
+
+    /// Returns the odds of the dealer drawing from `unseen`, given no blackjack if `peek`
+    pub(crate) fn odds(&self, unseen: &Composition, peek: bool) -> DealerOdds {
+        let mut odds = DealerOdds::default();
+        let longest = self.hands.iter().map(|hand| hand.len).max().unwrap_or(0);
+        // The chance of drawing a sequence of k cards, m of them of a value with c left, is
+        // the product over the values of c (c - 1) ... (c - m + 1), over n (n - 1) ... (n - k + 1)
+        let falling = |count: usize| {
+            let mut falling = vec![1.0; longest + 1];
+            for k in 1..=longest {
+                falling[k] = falling[k - 1] * count.saturating_sub(k - 1) as f64;
+            }
+            falling
+        };
+        let drawn = unseen
+            .iter()
+            .map(|(_, count)| falling(count))
+            .collect::<Vec<_>>();
+        let total = falling(unseen.len());
+        for hand in &self.hands {
+            if total[hand.len] == 0.0 {
+                continue;
+            }
+            let p = hand
+                .counts
+                .iter()
+                .fold(hand.orders, |p, &(value, count)| p * drawn[value][count]);
+            odds.probabilities[hand.outcome as usize] += p / total[hand.len];
+        }
+
+        let blackjack = &mut odds.probabilities[DealerOutcome::Blackjack as usize];
+        let no_blackjack = 1.0 - *blackjack;
+        if peek && *blackjack > 0.0 {
+            *blackjack = 0.0;
+            if no_blackjack > 0.0 {
+                for p in &mut odds.probabilities {
+                    *p /= no_blackjack;
+                }
+            }
+        }
+        odds
+    }
+}
@@ -0,0 +1,84 @@
+/*!
+
+  A single deck of cards
+
+*/
+
+use crate::card::Card;
+use rand::{Rng, seq::SliceRandom};
+use std::ops::Index;
+
+/// A deck of cards, full with the 52 cards of a standard deck when created
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deck {
+    /// The cards, top of the deck first
+    cards: Vec<Card>,
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deck {
+    /// Create a sorted 52-card deck
+    pub fn new() -> Self {
+        Self {
+            cards: Card::iter().collect(),
+        }
+    }
+
+    /// Returns the number of cards left in the deck
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns true if all the cards have been dealt
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Returns the cards, top of the deck first
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// An iterator over the cards, top of the deck first
+    pub fn iter(&self) -> impl Iterator<Item = &Card> {
+        self.cards.iter()
+    }
+
+    /// Sort the deck by suit and then by rank
+    pub fn sort(&mut self) {
+        self.cards.sort();
+    }
+
+    /// Shuffle the deck uniformly with `rng`
+    pub fn shuffle(&mut self, rng: &mut impl Rng) {
+        self.cards.shuffle(rng);
+    }
+
+    /// Deal the top card of the deck
+    pub fn deal(&mut self) -> Option<Card> {
+        (!self.cards.is_empty()).then(|| self.cards.remove(0))
+    }
+}
+
+impl Index<usize> for Deck {
+    type Output = Card;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.cards[index]
+    }
+}
+
+impl IntoIterator for Deck {
+    type Item = Card;
+    type IntoIter = std::vec::IntoIter<Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.into_iter()
+    }
+}
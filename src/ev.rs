@@ -0,0 +1,1108 @@
+/*!
+
+  Combinatorial expected values
+
+  An [Analyzer] finds the expected value of every legal action on a hand against a dealer
+  upcard, drawing every card from a [Composition] without replacement. Play after the action
+  is composition dependent: every later decision is the best one for the exact cards left.
+
+  Splits are approximated, so only the values of the other actions are exact. Each split
+  hand is played as though it were the only one, from the cards left after both pair cards
+  are removed but without the cards drawn to the other split hands, and resplits are only
+  limited by the splits made on the way to each hand.
+
+  Expected values are in units of the initial wager. With a peek under an ace or ten, they
+  are given the dealer does not have blackjack, since the player only acts then. Without a
+  peek, a dealer blackjack takes the whole wager, doubled or split. Either way, the player
+  draws from the unseen cards less the hole card, which is not one giving the dealer
+  blackjack: a ten is a little more likely to be drawn against an ace than the unseen cards
+  alone suggest.
+
+  A [HouseEdge] finds the edge of a set of rules for a player following basic strategy, and
+  how much each rule adds to or takes from it.
+
+  The [effects of removal](Analyzer::effects_of_removal) of each rank measure how a shoe
+  favors the player as cards leave it, and [indices](Analyzer::indices) derived from them
+  give the true counts at which to change plays for any [Counter]'s tags.
+  These analyze the shoe with each rank removed in turn, which an
+  [approximate](Analyzer::approximate) analyzer does quickly.
+
+*/
+
+use crate::{
+    card::{Card, Rank, Suit},
+    composition::{self, Composition, ranks},
+    dealer::{DealerHands, DealerOdds},
+    hand::{Hand, HandClass},
+    shoe::Counter,
+    strategy::{self, Chart, ChartEntry, ChartRow, PlayingStrategy},
+    table::{Action, TableRules},
+};
+use std::{collections::HashMap, fmt::Display};
+
+/// The expected value of each legal action on a hand
+///
+/// ```
+/// use shoo::{Composition, Hand, Rank, ev::Analyzer, table::{Action, TableRules}};
+///
+/// let hand: Hand = "10h 6c".parse().unwrap();
+/// let mut unseen = Composition::decks(6);
+/// for rank in [Rank::Ten, Rank::Six, Rank::Ten] {
+///     unseen.remove(rank);
+/// }
+/// let evs = Analyzer::new(TableRules::default()).evaluate(&hand, Rank::Ten, &unseen);
+/// assert_eq!(evs.best().0, Action::Surrender);
+/// assert!(evs.cost(Action::Stand).unwrap() > 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionEv {
+    /// The expected value of each action, in the order of [Action], if it is legal
+    evs: [Option<f64>; 5],
+}
+
+/// Every action, in the order of [ActionEv::evs]
+const ACTIONS: [Action; 5] = [
+    Action::Hit,
+    Action::Stand,
+    Action::Double,
+    Action::Split,
+    Action::Surrender,
+];
+
+impl ActionEv {
+    /// Returns the expected value of `action`, or `None` if it is not legal
+    pub fn get(&self, action: Action) -> Option<f64> {
+        self.evs[action as usize]
+    }
+
+    /// An iterator over the legal actions and their expected values
+    pub fn iter(&self) -> impl Iterator<Item = (Action, f64)> + '_ {
+        ACTIONS
+            .into_iter()
+            .filter_map(|action| self.get(action).map(|ev| (action, ev)))
+    }
+
+    /// Returns the legal action with the highest expected value and its expected value
+    pub fn best(&self) -> (Action, f64) {
+        self.iter()
+            .fold((Action::Stand, f64::NEG_INFINITY), |best, (action, ev)| {
+                if ev > best.1 { (action, ev) } else { best }
+            })
+    }
+
+    /// Returns the expected value given up by taking `action` instead of the best action,
+    /// or `None` if it is not legal
+    pub fn cost(&self, action: Action) -> Option<f64> {
+        self.get(action).map(|ev| self.best().1 - ev)
+    }
+
+    /// Set the expected value of `action`
+    fn set(&mut self, action: Action, ev: f64) {
+        self.evs[action as usize] = Some(ev);
+    }
+
+    /// Returns the chart entry playing the hand by these values, without splitting
+    fn entry(&self) -> ChartEntry {
+        let hit_or_stand = |evs: &Self| match (evs.get(Action::Hit), evs.get(Action::Stand)) {
+            (Some(hit), Some(stand)) if hit > stand => Action::Hit,
+            _ => Action::Stand,
+        };
+        let mut evs = *self;
+        evs.evs[Action::Split as usize] = None;
+        match (evs.best().0, hit_or_stand(&evs)) {
+            (Action::Double, Action::Hit) => ChartEntry::DoubleHit,
+            (Action::Double, _) => ChartEntry::DoubleStand,
+            (Action::Surrender, Action::Hit) => ChartEntry::SurrenderHit,
+            (Action::Surrender, _) => ChartEntry::SurrenderStand,
+            (Action::Hit, _) => ChartEntry::Hit,
+            _ => ChartEntry::Stand,
+        }
+    }
+
+    /// Returns the chart entry playing a pair by these values
+    fn pair_entry(&self) -> ChartEntry {
+        let Some(split) = self.get(Action::Split) else {
+            return self.entry();
+        };
+        let unsplit = self.entry();
+        let played = [Action::Hit, Action::Stand]
+            .into_iter()
+            .filter_map(|action| self.get(action))
+            .fold(f64::NEG_INFINITY, f64::max);
+        match self.best().0 {
+            Action::Split => ChartEntry::Split,
+            Action::Surrender if split > played => ChartEntry::SurrenderSplit,
+            _ => unsplit,
+        }
+    }
+}
+
+/// The largest true count, either way, of an index worth playing
+const MAX_INDEX: f64 = 10.0;
+
+/// The expected values of a two-card hand against each upcard, and how likely it is dealt.
+/// Each array is indexed by the upcard's column in a chart.
+struct TwoCards {
+    /// The hand
+    hand: Hand,
+    /// The chance of the hand being dealt against each upcard
+    weights: [f64; 10],
+    /// The expected values against each upcard
+    evs: [ActionEv; 10],
+    /// The chance of a dealer blackjack against each upcard when the dealer peeks, which the
+    /// expected values are given there is not
+    peeked: [f64; 10],
+}
+
+/// Finds the expected values of actions under a set of rules, caching the dealer's odds and
+/// the values of the hands played along the way
+#[derive(Debug, Clone)]
+pub struct Analyzer {
+    /// The rules of the table
+    rules: TableRules,
+    /// The dealer's odds without blackjack, by upcard and unseen cards
+    dealer: HashMap<(Rank, Composition), DealerOdds>,
+    /// The sets of cards the dealer can draw, by upcard
+    hands: HashMap<Rank, DealerHands>,
+    /// The value of hitting or standing, whichever is best, by upcard, unseen cards, hard
+    /// total and whether the hand holds an ace
+    played: HashMap<(Rank, Composition, u8, bool), f64>,
+    /// Whether the player's cards after the deal are drawn without removing them
+    approximate: bool,
+    /// The chart every decision after the first follows instead of the best play, if any
+    following: Option<Chart>,
+}
+
+/// Returns the rank of the same value as `rank`, which is [Rank::Ten] for ten-valued ranks
+fn normalize(rank: Rank) -> Rank {
+    if rank.is_ten_value() { Rank::Ten } else { rank }
+}
+
+/// Returns the hole card giving the dealer blackjack with `upcard`, if there is one
+fn hole(upcard: Rank) -> Option<Rank> {
+    match upcard {
+        Rank::Ace => Some(Rank::Ten),
+        Rank::Ten => Some(Rank::Ace),
+        _ => None,
+    }
+}
+
+/// Returns the chance of the player drawing a card of `rank` from `unseen` against `upcard`,
+/// given the hole card is one of the unseen cards not giving the dealer blackjack
+fn chance(upcard: Rank, unseen: &Composition, rank: Rank) -> f64 {
+    let total = unseen.len() as f64;
+    let others = hole(upcard).map_or(0.0, |hole| total - unseen.count(hole) as f64);
+    if others == 0.0 || total < 2.0 {
+        return unseen.probability(rank);
+    }
+    // Each hole card of another rank leaves one fewer card of its rank to draw
+    let count = unseen.count(rank) as f64;
+    if hole(upcard) == Some(rank) {
+        count / (total - 1.0)
+    } else {
+        count / (total - 1.0) * (1.0 - 1.0 / others)
+    }
+}
+
+/// Returns the total row of a chart for `hand`
+fn total_row(hand: &Hand) -> HandClass {
+    match hand.total() {
+        total if total.is_soft() => HandClass::Soft(total.total()),
+        total => HandClass::Hard(total.total()),
+    }
+}
+
+/// Returns the play of `chart` against `upcard` for a hand of three or more cards with a hard
+/// total of `hard`, holding an ace if `ace`
+fn chart_play(chart: &Chart, upcard: Rank, hard: u8, ace: bool) -> Action {
+    let total = best_total(hard, ace);
+    let row = if total > hard {
+        HandClass::Soft(total)
+    } else {
+        HandClass::Hard(total)
+    };
+    chart
+        .get(row, Card::new(Suit::Diamond, upcard))
+        .and_then(|entry| {
+            entry
+                .preferences()
+                .iter()
+                .copied()
+                .find(|&action| action == Action::Hit || action == Action::Stand)
+        })
+        .unwrap_or(Action::Stand)
+}
+
+/// Returns the best total of a hand with a hard total of `hard`, counting an ace as 11 if
+/// it holds one and does not bust
+fn best_total(hard: u8, ace: bool) -> u8 {
+    if ace && hard <= 11 { hard + 10 } else { hard }
+}
+
+impl Analyzer {
+    /// Create an analyzer for a table with `rules`. The number of decks and penetration are
+    /// ignored, as the unseen cards are given to each evaluation.
+    pub fn new(rules: TableRules) -> Self {
+        Self {
+            rules,
+            dealer: HashMap::new(),
+            hands: HashMap::new(),
+            played: HashMap::new(),
+            approximate: false,
+            following: None,
+        }
+    }
+
+    /// Draw the player's cards after the first two without removing them from the unseen
+    /// cards, so the dealer's odds are only found once per hand dealt. Charts and house edges
+    /// are found dozens of times faster, with house edges within about 0.01% of those found
+    /// drawing every card without replacement, down to a single deck. Values already found
+    /// are forgotten, so none are mixed across the two ways of drawing.
+    pub fn approximate(mut self) -> Self {
+        self.approximate = true;
+        self.dealer.clear();
+        self.played.clear();
+        self
+    }
+
+    /// Returns the rules of the table
+    pub fn rules(&self) -> &TableRules {
+        &self.rules
+    }
+
+    /// Returns the cards left after the player draws a card of `rank` from `unseen`
+    fn draw(&self, unseen: &Composition, rank: Rank) -> Composition {
+        if self.approximate {
+            *unseen
+        } else {
+            unseen.without(rank).unwrap()
+        }
+    }
+
+    /// Find the expected value of each legal action on the first hand of a round, `hand`,
+    /// against `upcard`, drawing from `unseen`: the cards not yet seen, without the upcard or
+    /// the player's cards. Doubling, splitting and surrendering are legal on two cards.
+    ///
+    /// # Panics
+    /// Panics if the hand has fewer than two cards
+    pub fn evaluate(&mut self, hand: &Hand, upcard: Rank, unseen: &Composition) -> ActionEv {
+        assert!(hand.len() >= 2, "a hand to evaluate has at least two cards");
+        let upcard = normalize(upcard);
+        let hard = hand.total().hard();
+        let ace = hand.cards().iter().any(|card| card.is_ace());
+        let mut evs = ActionEv::default();
+
+        let stand = if hand.blackjack() {
+            self.rules.blackjack_payout as f64
+        } else {
+            self.stand(upcard, unseen, best_total(hard, ace))
+        };
+        evs.set(Action::Stand, stand);
+        if hard < 21 {
+            evs.set(Action::Hit, self.hit(upcard, unseen, hard, ace));
+        }
+        if hand.len() == 2 {
+            evs.set(Action::Double, self.double(upcard, unseen, hard, ace));
+            let [a, b] = [hand.cards()[0], hand.cards()[1]];
+            if a.values() == b.values() && self.rules.max_splits > 0 {
+                let pair = normalize(a.rank());
+                evs.set(
+                    Action::Split,
+                    2.0 * self.split_hand(upcard, unseen, pair, 1),
+                );
+            }
+            if self.rules.surrender {
+                evs.set(Action::Surrender, -0.5);
+            }
+        }
+
+        let Some(hole) = hole(upcard) else {
+            return evs;
+        };
+        if !self.rules.peek {
+            let blackjack = unseen.probability(hole);
+            for action in ACTIONS {
+                let stake = match action {
+                    Action::Double | Action::Split => 2.0,
+                    Action::Stand if hand.blackjack() => 0.0,
+                    _ => 1.0,
+                };
+                if let Some(ev) = evs.get(action) {
+                    evs.set(action, (1.0 - blackjack) * ev - blackjack * stake);
+                }
+            }
+        }
+        evs
+    }
+
+    /// Derive total-dependent basic strategy for a shoe holding `shoe` before the deal: the
+    /// play for each total that is best on average over the two-card hands making it.
+    /// The composition-dependent value of each two-card hand is weighted by the chance of it
+    /// being dealt against the upcard. Pair rows that should not be split hold the play for
+    /// the pair's total, so the chart plays every two-card hand by its best action.
+    ///
+    /// ```no_run
+    /// use shoo::{Composition, ev::Analyzer, strategy::BasicStrategy, table::TableRules};
+    ///
+    /// let rules = TableRules { dealer_hits_soft_17: true, ..Default::default() };
+    /// let chart = Analyzer::new(rules).total_dependent_chart(&Composition::decks(rules.decks));
+    /// let strategy = BasicStrategy::new(chart);
+    /// ```
+    pub fn total_dependent_chart(&mut self, shoe: &Composition) -> Chart {
+        Self::chart(&self.two_card_evs(shoe))
+    }
+
+    /// Returns the mean expected values of each row of a chart against each upcard, over
+    /// the hands of `hands` making it weighted by the chance of each. Total rows leave out
+    /// splitting, and upcards no hand can be dealt against have no values.
+    fn rows(hands: &[TwoCards]) -> Vec<(ChartRow, [Option<ActionEv>; 10])> {
+        let mut totals: HashMap<HandClass, [(f64, ActionEv); 10]> = HashMap::new();
+        for two in hands {
+            let sums = totals.entry(total_row(&two.hand)).or_default();
+            for (column, (weight, sum)) in sums.iter_mut().enumerate() {
+                *weight += two.weights[column];
+                for (action, ev) in two.evs[column].iter() {
+                    if action != Action::Split {
+                        let total = sum.get(action).unwrap_or(0.0);
+                        sum.set(action, total + two.weights[column] * ev);
+                    }
+                }
+            }
+        }
+
+        let mut rows = Vec::new();
+        for row in (4..=21)
+            .map(HandClass::Hard)
+            .chain((12..=21).map(HandClass::Soft))
+        {
+            let means = totals.get(&row).map_or([None; 10], |sums| {
+                sums.map(|(weight, ev)| {
+                    let mut mean = ActionEv::default();
+                    for (action, sum) in ev.iter() {
+                        mean.set(action, sum / weight);
+                    }
+                    (weight > 0.0).then_some(mean)
+                })
+            });
+            rows.push((row, means));
+        }
+        for two in hands {
+            if let Some(pair) = two.hand.total().pair() {
+                let evs = std::array::from_fn(|column| {
+                    (two.weights[column] > 0.0).then_some(two.evs[column])
+                });
+                rows.push((HandClass::Pair(pair), evs));
+            }
+        }
+        rows
+    }
+
+    /// Returns the total-dependent chart for the values of `hands`
+    fn chart(hands: &[TwoCards]) -> Chart {
+        let mut chart = Chart::default();
+        for (row, means) in Self::rows(hands) {
+            let entries = means.map(|evs| match (row, evs) {
+                (_, None) => ChartEntry::Stand,
+                (HandClass::Pair(_), Some(evs)) => evs.pair_entry(),
+                (_, Some(evs)) => evs.entry(),
+            });
+            chart.set(row, entries);
+        }
+        chart
+    }
+
+    /// Derive composition-dependent basic strategy for a shoe holding `shoe` before the deal:
+    /// the [total-dependent chart](Self::total_dependent_chart), with a two-card row for
+    /// each two-card hand whose best play differs from its total's row against some upcard.
+    /// Hands of three or more cards are played by their totals.
+    pub fn composition_dependent_chart(&mut self, shoe: &Composition) -> Chart {
+        let hands = self.two_card_evs(shoe);
+        let mut chart = Self::chart(&hands);
+        for two in hands.iter().filter(|two| two.hand.total().pair().is_none()) {
+            let row = total_row(&two.hand);
+            let totals = chart
+                .rows()
+                .find(|&(r, _)| r == row)
+                .map(|(_, entries)| *entries);
+            let Some(totals) = totals else {
+                continue;
+            };
+            let entries = std::array::from_fn(|column| {
+                if two.weights[column] > 0.0 {
+                    two.evs[column].entry()
+                } else {
+                    totals[column]
+                }
+            });
+            if entries != totals {
+                let [a, b] = [two.hand.cards()[0], two.hand.cards()[1]];
+                chart.set_cards([a.rank(), b.rank()], entries);
+            }
+        }
+        chart
+    }
+
+    /// Find the house edge of a shoe holding `shoe` before the deal, making every decision by
+    /// the [total-dependent chart](Self::total_dependent_chart) for it: the units the player
+    /// loses per unit of initial wager, negative when the player has the edge.
+    pub fn house_edge(&mut self, shoe: &Composition) -> f64 {
+        let chart = Self::chart(&self.two_card_evs(shoe));
+        self.follow(&chart, |analyzer| {
+            -Self::player_ev(&analyzer.two_card_evs(shoe), &chart, shoe)
+        })
+    }
+
+    /// Find the effect on the player's expected value of removing one card of each rank from
+    /// a shoe holding `shoe`, making every decision by the
+    /// [total-dependent chart](Self::total_dependent_chart) for the full shoe
+    pub fn effects_of_removal(&mut self, shoe: &Composition) -> EffectsOfRemoval {
+        let chart = Self::chart(&self.two_card_evs(shoe));
+        self.follow(&chart, |analyzer| {
+            let ev = Self::player_ev(&analyzer.two_card_evs(shoe), &chart, shoe);
+            let mut effects = [0.0; 10];
+            for (effect, (rank, _)) in effects.iter_mut().zip(shoe.iter()) {
+                if let Some(removed) = shoe.without(rank) {
+                    let hands = analyzer.two_card_evs(&removed);
+                    *effect = Self::player_ev(&hands, &chart, &removed) - ev;
+                }
+            }
+            EffectsOfRemoval { ev, effects }
+        })
+    }
+
+    /// Returns `f` run while every decision after the first on a hand follows `chart`. Values
+    /// played the other way are forgotten before and after.
+    fn follow<T>(&mut self, chart: &Chart, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.following = Some(chart.clone());
+        self.played.clear();
+        let result = f(self);
+        self.following = None;
+        self.played.clear();
+        result
+    }
+
+    /// Find the true count at which each play of the
+    /// [total-dependent chart](Self::total_dependent_chart) for a shoe holding `shoe` should
+    /// change, counting with the tags of `C`.
+    ///
+    /// The gain of each other play over the chart's is estimated as a linear function of the
+    /// true count, from the effects of removing each rank on it, and the index is where the
+    /// gain is zero. Only splitting or not is indexed for pairs, and indices beyond a true
+    /// count of 10 either way are left out. For unbalanced tags, the true count is relative to
+    /// the count expected of the cards dealt.
+    pub fn indices<C: Counter>(&mut self, shoe: &Composition) -> IndexTable {
+        let hands = self.two_card_evs(shoe);
+        let chart = Self::chart(&hands);
+        let rows = Self::rows(&hands);
+        let removed = shoe
+            .iter()
+            .filter_map(|(rank, _)| {
+                let removed = shoe.without(rank)?;
+                let rows = Self::rows(&self.two_card_evs(&removed));
+                Some((shoe.probability(rank), C::tag(rank) as f64, rows))
+            })
+            .collect::<Vec<_>>();
+        let mean_tag = removed.iter().map(|&(p, tag, _)| p * tag).sum::<f64>();
+        let variance = removed
+            .iter()
+            .map(|&(p, tag, _)| p * (tag - mean_tag).powi(2))
+            .sum::<f64>();
+        let decks = shoe.len() as f64 / 52.0;
+
+        let mut indices = Vec::new();
+        if variance == 0.0 {
+            return IndexTable { indices };
+        }
+        for (i, &(row, means)) in rows.iter().enumerate() {
+            for upcard in ranks() {
+                let column = strategy::column(upcard);
+                let Some(evs) = means[column] else {
+                    continue;
+                };
+                let Some(&basic) =
+                    chart
+                        .get(row, Card::new(Suit::Diamond, upcard))
+                        .and_then(|entry| {
+                            entry
+                                .preferences()
+                                .iter()
+                                .find(|&&action| evs.get(action).is_some())
+                        })
+                else {
+                    continue;
+                };
+                let pair = matches!(row, HandClass::Pair(_));
+                for (deviation, ev) in evs.iter() {
+                    if deviation == basic
+                        || (pair && (deviation == Action::Split) == (basic == Action::Split))
+                    {
+                        continue;
+                    }
+                    let gain = |evs: &ActionEv| Some(evs.get(deviation)? - evs.get(basic)?);
+                    let base = ev - evs.get(basic).unwrap_or_default();
+                    let effects = removed
+                        .iter()
+                        .map(|&(p, tag, ref rows)| {
+                            let effect = rows[i].1[column].as_ref().and_then(gain)? - base;
+                            Some((p, tag, effect))
+                        })
+                        .collect::<Option<Vec<_>>>();
+                    let Some(effects) = effects else {
+                        continue;
+                    };
+                    let mean_effect = effects.iter().map(|&(p, _, e)| p * e).sum::<f64>();
+                    let per_tag = effects
+                        .iter()
+                        .map(|&(p, tag, e)| p * (e - mean_effect) * (tag - mean_tag))
+                        .sum::<f64>()
+                        / variance;
+                    let per_count = per_tag * decks;
+                    let true_count = -base / per_count;
+                    if per_count != 0.0 && true_count.abs() <= MAX_INDEX {
+                        indices.push(Index {
+                            row,
+                            upcard,
+                            basic,
+                            deviation,
+                            true_count: true_count as f32,
+                            above: per_count > 0.0,
+                        });
+                    }
+                }
+            }
+        }
+        IndexTable { indices }
+    }
+
+    /// Returns the player's expected value for a shoe holding `shoe`, playing the first
+    /// action on the values of `hands` by `chart`
+    fn player_ev(hands: &[TwoCards], chart: &Chart, shoe: &Composition) -> f64 {
+        let mut ev = 0.0;
+        for two in hands {
+            for upcard in ranks() {
+                let column = strategy::column(upcard);
+                let evs = two.evs[column];
+                let allowed = evs.iter().map(|(action, _)| action).collect::<Vec<_>>();
+                if two.weights[column] == 0.0 || allowed.is_empty() {
+                    continue;
+                }
+                let action = chart.action(&two.hand, Card::new(Suit::Diamond, upcard), &allowed);
+                let played = evs.get(action).unwrap_or_default();
+                // A dealer blackjack found by the peek pushes a blackjack and takes any other hand
+                let blackjack = two.peeked[column];
+                let lost = if two.hand.blackjack() { 0.0 } else { 1.0 };
+                ev += shoe.probability(upcard)
+                    * two.weights[column]
+                    * ((1.0 - blackjack) * played - blackjack * lost);
+            }
+        }
+        ev
+    }
+
+    /// Returns the expected values of every two-card hand against every upcard, dealt from
+    /// `shoe`
+    fn two_card_evs(&mut self, shoe: &Composition) -> Vec<TwoCards> {
+        let mut hands = Vec::new();
+        for first in ranks() {
+            for second in ranks().filter(|&second| second >= first) {
+                let mut hand = Hand::default();
+                hand.insert(Card::new(Suit::Spade, first));
+                hand.insert(Card::new(Suit::Heart, second));
+                let mut two = TwoCards {
+                    hand,
+                    weights: [0.0; 10],
+                    evs: [ActionEv::default(); 10],
+                    peeked: [0.0; 10],
+                };
+                for upcard in ranks() {
+                    let column = strategy::column(upcard);
+                    let Some(unseen) = shoe
+                        .without(upcard)
+                        .and_then(|unseen| {
+                            let weight = unseen.probability(first);
+                            unseen.without(first).map(|unseen| (weight, unseen))
+                        })
+                        .and_then(|(weight, unseen)| {
+                            let weight = weight * unseen.probability(second);
+                            unseen.without(second).map(|unseen| (weight, unseen))
+                        })
+                    else {
+                        continue;
+                    };
+                    let (weight, unseen) = unseen;
+                    two.weights[column] = if first == second {
+                        weight
+                    } else {
+                        2.0 * weight
+                    };
+                    two.evs[column] = self.evaluate(&two.hand, upcard, &unseen);
+                    if self.rules.peek {
+                        two.peeked[column] =
+                            hole(upcard).map_or(0.0, |hole| unseen.probability(hole));
+                    }
+                }
+                hands.push(two);
+            }
+        }
+        hands
+    }
+
+    /// Returns the dealer's odds against `upcard` drawing from `unseen`, given no blackjack
+    fn dealer_odds(&mut self, upcard: Rank, unseen: &Composition) -> DealerOdds {
+        if let Some(&odds) = self.dealer.get(&(upcard, *unseen)) {
+            return odds;
+        }
+        let hits_soft_17 = self.rules.dealer_hits_soft_17;
+        let odds = self
+            .hands
+            .entry(upcard)
+            .or_insert_with(|| DealerHands::new(upcard, hits_soft_17))
+            .odds(unseen, true);
+        self.dealer.insert((upcard, *unseen), odds);
+        odds
+    }
+
+    /// Returns the expected value of standing on `total`
+    fn stand(&mut self, upcard: Rank, unseen: &Composition, total: u8) -> f64 {
+        if total > 21 {
+            return -1.0;
+        }
+        self.dealer_odds(upcard, unseen)
+            .iter()
+            .map(|(outcome, p)| match outcome.total() {
+                None => p,
+                Some(dealer) if total > dealer => p,
+                Some(dealer) if total < dealer => -p,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Returns the expected value of hitting a hand with a hard total of `hard`, playing
+    /// each card drawn as well as possible
+    fn hit(&mut self, upcard: Rank, unseen: &Composition, hard: u8, ace: bool) -> f64 {
+        let mut ev = 0.0;
+        for (rank, _) in unseen.iter() {
+            let p = chance(upcard, unseen, rank);
+            if p > 0.0 {
+                let unseen = self.draw(unseen, rank);
+                ev += p * self.play(
+                    upcard,
+                    &unseen,
+                    hard + rank.value(),
+                    ace || rank == Rank::Ace,
+                );
+            }
+        }
+        ev
+    }
+
+    /// Returns the expected value of the better of hitting and standing, or of the play of
+    /// the chart being followed
+    fn play(&mut self, upcard: Rank, unseen: &Composition, hard: u8, ace: bool) -> f64 {
+        if hard > 21 {
+            return -1.0;
+        }
+        let key = (upcard, *unseen, hard, ace);
+        if let Some(&ev) = self.played.get(&key) {
+            return ev;
+        }
+        let total = best_total(hard, ace);
+        let stand = self.stand(upcard, unseen, total);
+        let chart = self
+            .following
+            .as_ref()
+            .map(|chart| chart_play(chart, upcard, hard, ace));
+        let ev = match chart {
+            _ if total == 21 => stand,
+            Some(Action::Stand) => stand,
+            Some(_) => self.hit(upcard, unseen, hard, ace),
+            None => stand.max(self.hit(upcard, unseen, hard, ace)),
+        };
+        self.played.insert(key, ev);
+        ev
+    }
+
+    /// Returns the expected value of doubling, taking exactly one more card
+    fn double(&mut self, upcard: Rank, unseen: &Composition, hard: u8, ace: bool) -> f64 {
+        let mut ev = 0.0;
+        for (rank, _) in unseen.iter() {
+            let p = chance(upcard, unseen, rank);
+            if p > 0.0 {
+                let unseen = self.draw(unseen, rank);
+                let total = best_total(hard + rank.value(), ace || rank == Rank::Ace);
+                ev += p * self.stand(upcard, &unseen, total);
+            }
+        }
+        2.0 * ev
+    }
+
+    /// Returns the expected value of one hand split from a pair of `pair`s, after `splits`
+    /// splits on the way to it, making the best legal play or that of the chart being followed
+    fn split_hand(&mut self, upcard: Rank, unseen: &Composition, pair: Rank, splits: usize) -> f64 {
+        let aces = pair == Rank::Ace;
+        let mut ev = 0.0;
+        for (rank, _) in unseen.iter() {
+            let p = chance(upcard, unseen, rank);
+            if p == 0.0 {
+                continue;
+            }
+            let unseen = self.draw(unseen, rank);
+            let hard = pair.value() + rank.value();
+            let ace = aces || rank == Rank::Ace;
+            let mut allowed = vec![Action::Stand];
+            if !aces || self.rules.hit_split_aces {
+                allowed.push(Action::Hit);
+            }
+            if self.rules.double_after_split && !aces {
+                allowed.push(Action::Double);
+            }
+            if rank == pair && splits < self.rules.max_splits && (!aces || self.rules.resplit_aces)
+            {
+                allowed.push(Action::Split);
+            }
+            if let Some(chart) = &self.following {
+                let mut hand = Hand::default();
+                hand.insert(Card::new(Suit::Spade, pair));
+                hand.insert(Card::new(Suit::Heart, rank));
+                let action = chart.action(&hand, Card::new(Suit::Diamond, upcard), &allowed);
+                allowed = vec![action];
+            }
+            let mut best = f64::NEG_INFINITY;
+            for action in allowed {
+                let played = match action {
+                    Action::Hit => self.hit(upcard, &unseen, hard, ace),
+                    Action::Double => self.double(upcard, &unseen, hard, ace),
+                    Action::Split => 2.0 * self.split_hand(upcard, &unseen, pair, splits + 1),
+                    _ => self.stand(upcard, &unseen, best_total(hard, ace)),
+                };
+                best = best.max(played);
+            }
+            ev += p * best;
+        }
+        ev
+    }
+}
+
+/// A rule of a table that changes the house edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rule {
+    /// The number of decks in the shoe
+    Decks,
+    /// Whether the dealer hits a soft 17
+    DealerHitsSoft17,
+    /// Whether the dealer peeks for blackjack
+    Peek,
+    /// The payout for a blackjack
+    BlackjackPayout,
+    /// Whether late surrender is allowed
+    Surrender,
+    /// Whether a hand can be doubled after a split
+    DoubleAfterSplit,
+    /// The maximum number of splits
+    MaxSplits,
+    /// Whether split aces can be split again
+    ResplitAces,
+    /// Whether split aces can take more than one card
+    HitSplitAces,
+}
+
+impl Rule {
+    /// Every rule, in the order they are changed to break down a house edge
+    pub const ALL: [Rule; 9] = [
+        Rule::Decks,
+        Rule::DealerHitsSoft17,
+        Rule::Peek,
+        Rule::BlackjackPayout,
+        Rule::Surrender,
+        Rule::DoubleAfterSplit,
+        Rule::MaxSplits,
+        Rule::ResplitAces,
+        Rule::HitSplitAces,
+    ];
+
+    /// An iterator over every rule, in the order they are changed to break down a house edge
+    pub fn iter() -> impl Iterator<Item = Rule> {
+        Self::ALL.into_iter()
+    }
+
+    /// Returns `rules` with this rule taken from `from`
+    fn apply(self, mut rules: TableRules, from: &TableRules) -> TableRules {
+        match self {
+            Self::Decks => rules.decks = from.decks,
+            Self::DealerHitsSoft17 => rules.dealer_hits_soft_17 = from.dealer_hits_soft_17,
+            Self::Peek => rules.peek = from.peek,
+            Self::BlackjackPayout => rules.blackjack_payout = from.blackjack_payout,
+            Self::Surrender => rules.surrender = from.surrender,
+            Self::DoubleAfterSplit => rules.double_after_split = from.double_after_split,
+            Self::MaxSplits => rules.max_splits = from.max_splits,
+            Self::ResplitAces => rules.resplit_aces = from.resplit_aces,
+            Self::HitSplitAces => rules.hit_split_aces = from.hit_split_aces,
+        }
+        rules
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Decks => "Decks",
+            Self::DealerHitsSoft17 => "Dealer hits soft 17",
+            Self::Peek => "Peek",
+            Self::BlackjackPayout => "Blackjack payout",
+            Self::Surrender => "Surrender",
+            Self::DoubleAfterSplit => "Double after split",
+            Self::MaxSplits => "Max splits",
+            Self::ResplitAces => "Resplit aces",
+            Self::HitSplitAces => "Hit split aces",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The house edge of a set of rules off the top of the shoe, broken down into the change each
+/// rule makes from a baseline set of rules.
+///
+/// The rules are changed from the baseline one at a time in the order of [Rule::ALL], and each
+/// rule's contribution is the change in the edge it makes, so the contributions sum to the
+/// difference between the two edges. Every decision follows the
+/// [total-dependent chart](Analyzer::total_dependent_chart) for each set of rules along the
+/// way, as in [Analyzer::house_edge].
+///
+/// ```no_run
+/// use shoo::{ev::HouseEdge, table::TableRules};
+///
+/// let rules = TableRules { blackjack_payout: 1.2, penetration: 1.0, ..Default::default() };
+/// let edge = HouseEdge::new(rules);
+/// for (rule, contribution) in edge.contributions() {
+///     println!("{rule}: {:+.3}%", 100.0 * contribution);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HouseEdge {
+    /// The rules
+    rules: TableRules,
+    /// The house edge of the rules
+    edge: f64,
+    /// The house edge of the baseline rules
+    baseline: f64,
+    /// The change in the edge made by each rule, in the order of [Rule::ALL]
+    contributions: [f64; 9],
+}
+
+impl HouseEdge {
+    /// Find the house edge of `rules`, broken down from the [default rules](TableRules::default)
+    /// dealt without a cut card
+    ///
+    /// # Panics
+    /// Panics if `rules` has a penetration below 1.0
+    pub fn new(rules: TableRules) -> Self {
+        let baseline = TableRules {
+            penetration: 1.0,
+            ..Default::default()
+        };
+        Self::compare(rules, baseline)
+    }
+
+    /// Find the house edge of `rules`, broken down from `baseline`
+    ///
+    /// # Panics
+    /// Panics if either set of rules has a penetration below 1.0. The edge is found for a shoe
+    /// dealt without a cut card, as the cut card's effect on the edge is not modeled.
+    pub fn compare(rules: TableRules, baseline: TableRules) -> Self {
+        assert!(
+            rules.penetration >= 1.0 && baseline.penetration >= 1.0,
+            "a house edge is found without a cut card, at a penetration of 1.0"
+        );
+        let mut analyzer = Analyzer::new(baseline);
+        let edge = |analyzer: &mut Analyzer| {
+            let shoe = Composition::decks(analyzer.rules.decks);
+            analyzer.house_edge(&shoe)
+        };
+        let baseline = edge(&mut analyzer);
+        let mut current = baseline;
+        let mut contributions = [0.0; 9];
+        for (rule, contribution) in Rule::iter().zip(&mut contributions) {
+            let changed = rule.apply(analyzer.rules, &rules);
+            if changed == analyzer.rules {
+                continue;
+            }
+            if changed.dealer_hits_soft_17 == analyzer.rules.dealer_hits_soft_17 {
+                // Only the dealer's odds and the hands played from them depend on soft 17
+                analyzer.rules = changed;
+            } else {
+                analyzer = Analyzer::new(changed);
+            }
+            let next = edge(&mut analyzer);
+            *contribution = next - current;
+            current = next;
+        }
+        Self {
+            rules,
+            edge: current,
+            baseline,
+            contributions,
+        }
+    }
+
+    /// Returns the rules
+    pub fn rules(&self) -> &TableRules {
+        &self.rules
+    }
+
+    /// Returns the house edge, the units the player loses per unit of initial wager. It is
+    /// negative when the player has the edge.
+    pub fn edge(&self) -> f64 {
+        self.edge
+    }
+
+    /// Returns the house edge of the baseline rules
+    pub fn baseline(&self) -> f64 {
+        self.baseline
+    }
+
+    /// Returns the change in the edge made by `rule`, positive when the rule favors the house
+    pub fn contribution(&self, rule: Rule) -> f64 {
+        self.contributions[rule as usize]
+    }
+
+    /// An iterator over the change in the edge made by each rule
+    pub fn contributions(&self) -> impl Iterator<Item = (Rule, f64)> + '_ {
+        Rule::iter().map(|rule| (rule, self.contribution(rule)))
+    }
+}
+
+/// The effect on the player's expected value of removing one card of each rank from a shoe,
+/// playing basic strategy for the full shoe
+///
+/// ```no_run
+/// use shoo::{Composition, ev::Analyzer, table::TableRules};
+///
+/// let rules = TableRules { decks: 1, ..Default::default() };
+/// let effects = Analyzer::new(rules).effects_of_removal(&Composition::decks(1));
+/// for (rank, effect) in effects.iter() {
+///     println!("{rank}: {:+.3}%", 100.0 * effect);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EffectsOfRemoval {
+    /// The player's expected value for the full shoe
+    ev: f64,
+    /// The change in the expected value removing each rank, in the order of [Composition::iter]
+    effects: [f64; 10],
+}
+
+impl EffectsOfRemoval {
+    /// Returns the player's expected value for the full shoe
+    pub fn ev(&self) -> f64 {
+        self.ev
+    }
+
+    /// Returns the change in the player's expected value removing one card of `rank`
+    pub fn get(&self, rank: Rank) -> f64 {
+        self.effects[composition::index(rank)]
+    }
+
+    /// An iterator over the change in the player's expected value removing each rank, as the
+    /// ace, 2 through 9 and [Rank::Ten]
+    pub fn iter(&self) -> impl Iterator<Item = (Rank, f64)> + '_ {
+        ranks().map(|rank| (rank, self.get(rank)))
+    }
+
+    /// Returns the betting correlation of the tags of `C`: the correlation of the tags of the
+    /// cards of a deck with the effects of removing them
+    pub fn correlation<C: Counter>(&self) -> f64 {
+        let (mut tags, mut effects, mut products) = (0.0, 0.0, 0.0);
+        let (mut tag_squares, mut effect_squares) = (0.0, 0.0);
+        for (rank, effect) in self.iter() {
+            let cards = if rank == Rank::Ten { 4.0 } else { 1.0 };
+            let tag = C::tag(rank) as f64;
+            tags += cards * tag;
+            effects += cards * effect;
+            products += cards * tag * effect;
+            tag_squares += cards * tag * tag;
+            effect_squares += cards * effect * effect;
+        }
+        let n = 13.0;
+        let covariance = products - tags * effects / n;
+        let variances = (tag_squares - tags * tags / n) * (effect_squares - effects * effects / n);
+        covariance / variances.sqrt()
+    }
+}
+
+/// The true count at which to change a play of basic strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Index {
+    /// The row of the chart
+    pub row: ChartRow,
+    /// The dealer's upcard, as [Rank::Ten] for any ten
+    pub upcard: Rank,
+    /// The basic strategy play
+    pub basic: Action,
+    /// The play to make instead past the index
+    pub deviation: Action,
+    /// The true count at which both plays are as good
+    pub true_count: f32,
+    /// Whether to deviate at true counts above the index rather than below it
+    pub above: bool,
+}
+
+impl Display for Index {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} vs {}: {} instead of {} at {:+.1} and {}",
+            self.row,
+            self.upcard,
+            self.deviation,
+            self.basic,
+            self.true_count,
+            if self.above { "above" } else { "below" }
+        )
+    }
+}
+
+/// The indices of the plays of a basic strategy chart for a counting system, by row and
+/// upcard
+///
+/// ```no_run
+/// use shoo::{Composition, HiLoCounter, ev::Analyzer, table::TableRules};
+///
+/// let rules = TableRules::default();
+/// let indices = Analyzer::new(rules).indices::<HiLoCounter>(&Composition::decks(rules.decks));
+/// println!("{indices}");
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexTable {
+    /// The indices, by row and upcard
+    indices: Vec<Index>,
+}
+
+impl IndexTable {
+    /// An iterator over the indices
+    pub fn iter(&self) -> impl Iterator<Item = &Index> {
+        self.indices.iter()
+    }
+
+    /// An iterator over the indices of the plays of `row` against `upcard`
+    pub fn get(&self, row: ChartRow, upcard: Rank) -> impl Iterator<Item = &Index> {
+        let upcard = normalize(upcard);
+        self.iter()
+            .filter(move |index| index.row == row && index.upcard == upcard)
+    }
+}
+
+impl Display for IndexTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for index in &self.indices {
+            writeln!(f, "{index}")?;
+        }
+        Ok(())
+    }
+}
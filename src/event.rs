@@ -0,0 +1,35 @@
+/*!
+
+  Events emitted by a shoe or table
+
+*/
+
+use crate::card::Card;
+
+/// Something that happened at the shoe or table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    /// A card was dealt from the shoe
+    CardDealt {
+        /// The card dealt
+        card: Card,
+        /// Whether the card was dealt face up
+        face_up: bool,
+    },
+    /// A card dealt face down was turned face up
+    CardRevealed(Card),
+    /// A dealt card was returned to the top of the shoe
+    CardReturned(Card),
+    /// The shoe started being shuffled
+    ShuffleStarted,
+    /// The cut card came out of the shoe
+    CutCardReached,
+    /// A round started at the table
+    RoundStarted,
+    /// A round was settled and the hands cleared
+    RoundSettled,
+}
+
+/// A callback subscribed to [Event]s
+pub(crate) type Observer = Box<dyn FnMut(&Event) + Send>;
@@ -4,11 +4,15 @@
 
 */
 
-use crate::card::Card;
-use std::fmt::Display;
+use crate::{
+    card::{Card, ParseCardError, Rank},
+    render::Renderer,
+};
+use std::{fmt::Display, str::FromStr};
 
 /// The value of a hand of cards
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Value {
     /// Ace and 10
     Blackjack,
@@ -32,8 +36,140 @@ impl Value {
     }
 }
 
+/// The class of a hand that strategy charts and statistics are keyed on,
+/// written as `Hard16`, `Soft18`, `Pair8`, `Pair10` or `PairA`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandClass {
+    /// A hard total
+    Hard(u8),
+    /// A soft total
+    Soft(u8),
+    /// A pair of cards of the same value. Ten-valued pairs use [Rank::Ten].
+    Pair(Rank),
+}
+
+impl Display for HandClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hard(v) => write!(f, "Hard{v}"),
+            Self::Soft(v) => write!(f, "Soft{v}"),
+            Self::Pair(r) => write!(f, "Pair{r}"),
+        }
+    }
+}
+
+/// An error parsing a [HandClass]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHandClassError(String);
+
+impl Display for ParseHandClassError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid hand class `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseHandClassError {}
+
+impl FromStr for HandClass {
+    type Err = ParseHandClassError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseHandClassError(s.to_string());
+        if let Some(v) = s.strip_prefix("Hard") {
+            v.parse().map(Self::Hard).map_err(|_| err())
+        } else if let Some(v) = s.strip_prefix("Soft") {
+            v.parse().map(Self::Soft).map_err(|_| err())
+        } else if let Some(r) = s.strip_prefix("Pair") {
+            let rank: Rank = r.parse().map_err(|_| err())?;
+            Ok(Self::Pair(if rank.is_ten_value() {
+                Rank::Ten
+            } else {
+                rank
+            }))
+        } else {
+            Err(err())
+        }
+    }
+}
+
+/// Hand classes serialize as their names ("Hard16"), so that they can key maps
+#[cfg(feature = "serde")]
+impl serde::Serialize for HandClass {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for HandClass {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The total of a hand: how its aces are counted and whether it is a pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandTotal {
+    /// The total counting every ace as 1
+    hard: u8,
+    /// The number of aces counted as 11
+    aces_high: u8,
+    /// The rank of a pair of cards of the same value
+    pair: Option<Rank>,
+}
+
+impl HandTotal {
+    /// Returns the total counting every ace as 1
+    pub fn hard(&self) -> u8 {
+        self.hard
+    }
+
+    /// Returns the best total, counting an ace as 11 when that does not bust the hand
+    pub fn total(&self) -> u8 {
+        self.hard + 10 * self.aces_high
+    }
+
+    /// Returns the total counting an ace as 11, if the hand is soft
+    pub fn soft(&self) -> Option<u8> {
+        self.is_soft().then(|| self.total())
+    }
+
+    /// Returns true if an ace is counted as 11
+    pub fn is_soft(&self) -> bool {
+        self.aces_high > 0
+    }
+
+    /// Returns true if the total is over 21
+    pub fn is_bust(&self) -> bool {
+        self.hard > 21
+    }
+
+    /// Returns the number of aces counted as 11 (0 or 1)
+    pub fn aces_high(&self) -> u8 {
+        self.aces_high
+    }
+
+    /// Returns the rank of a two-card pair of the same value. Ten-valued pairs use [Rank::Ten].
+    pub fn pair(&self) -> Option<Rank> {
+        self.pair
+    }
+
+    /// Returns the class of the hand: its pair if it is one, otherwise its soft or hard total.
+    /// A blackjack is `Soft21` and a busted hand is its hard total.
+    pub fn class(&self) -> HandClass {
+        match self.pair {
+            Some(rank) => HandClass::Pair(rank),
+            None if self.is_soft() => HandClass::Soft(self.total()),
+            None => HandClass::Hard(self.total()),
+        }
+    }
+}
+
 /// Represents a hand of cards in blackjack
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hand {
     /// The cards in the order they were dealt
     cards: Vec<Card>,
@@ -159,9 +295,37 @@ impl Hand {
         self.cards.len()
     }
 
-    /// Returns true if the hand is a pair (can split)
+    /// Returns the cards in the order they were dealt
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Returns true if the hand is a pair of cards of the same value (can split), as
+    /// [HandTotal::pair]
     pub fn pairs(&self) -> bool {
-        self.cards.len() == 2 && self.cards[0].rank() == self.cards[1].rank()
+        self.total().pair().is_some()
+    }
+
+    /// Returns the total of the hand
+    pub fn total(&self) -> HandTotal {
+        let hard = self
+            .cards
+            .iter()
+            .map(|c| c.values()[0] as u16)
+            .sum::<u16>()
+            .min(u8::MAX as u16) as u8;
+        let pair = match self.cards[..] {
+            [a, b] if a.values() == b.values() => Some(match a.values()[0] {
+                10 => Rank::Ten,
+                _ => a.rank(),
+            }),
+            _ => None,
+        };
+        HandTotal {
+            hard,
+            aces_high: u8::from(self.num_aces > 0 && hard <= 11),
+            pair,
+        }
     }
 
     /// Returns true if the hand is a soft hand (contains an ace counted as 11)
@@ -175,6 +339,11 @@ impl Hand {
             return false;
         }
 
+        let total = self.total();
+        if total.is_soft() != self.is_soft() || total.is_bust() != self.busted() {
+            return false;
+        }
+
         if let Some(v) = self.value()
             && v > 21
         {
@@ -198,14 +367,40 @@ impl Hand {
     }
 }
 
+impl Hand {
+    /// Returns the value of the hand as printed after its cards
+    pub(crate) fn summary(&self) -> String {
+        match self.val {
+            Value::Blackjack => "(Blackjack)".to_string(),
+            Value::Soft(v, _) => format!("(Soft {v})"),
+            Value::Hard(v) => format!("(Hard {v})"),
+            Value::Bust => "(Bust)".to_string(),
+        }
+    }
+}
+
 impl Display for Hand {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let cards: Vec<String> = self.cards.iter().map(|c| format!("{}", c)).collect();
-        let cards_str = cards.join(", ");
-        match self.value() {
-            Some(_v) => write!(f, "[{}] (Value: {:?})", cards_str, self.val),
-            None => write!(f, "[{}] (Bust)", cards_str),
+        write!(f, "{}", Renderer::default().hand(self))
+    }
+}
+
+impl FromStr for Hand {
+    type Err = ParseCardError;
+
+    /// Parses cards separated by spaces or commas, such as `As Kh`.
+    /// The output of [Display] is accepted too.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = match s.strip_prefix('[').and_then(|s| s.split_once(']')) {
+            Some((cards, _)) => cards,
+            None => s,
+        };
+        let mut hand = Hand::default();
+        for card in s.split([' ', ',']).filter(|c| !c.is_empty()) {
+            hand.insert(card.parse()?);
         }
+        Ok(hand)
     }
 }
 
@@ -298,4 +493,47 @@ mod tests {
         assert!(!hand.blackjack());
         assert!(!hand.busted());
     }
+
+    #[test]
+    fn hand_total_soft() {
+        let hand: Hand = "Ah 7d".parse().unwrap();
+        let total = hand.total();
+        assert_eq!(total.hard(), 8);
+        assert_eq!(total.total(), 18);
+        assert_eq!(total.soft(), Some(18));
+        assert_eq!(total.aces_high(), 1);
+        assert_eq!(total.class(), HandClass::Soft(18));
+        assert_eq!(hand.to_string(), "[A♡, 7♢] (Soft 18)");
+    }
+
+    #[test]
+    fn hand_total_hard_and_bust() {
+        let hand: Hand = "Ah 7d 9c".parse().unwrap();
+        assert_eq!(hand.total().class(), HandClass::Hard(17));
+        assert_eq!(hand.total().soft(), None);
+
+        let hand: Hand = "Th 7d 9c".parse().unwrap();
+        assert!(hand.total().is_bust());
+        assert_eq!(hand.total().class(), HandClass::Hard(26));
+        assert_eq!(hand.to_string(), "[10♡, 7♢, 9♧] (Bust)");
+    }
+
+    #[test]
+    fn hand_class_pairs() {
+        let hand: Hand = "Kh Qd".parse().unwrap();
+        assert!(hand.pairs());
+        assert_eq!(hand.total().pair(), Some(Rank::Ten));
+        assert_eq!(hand.total().class(), HandClass::Pair(Rank::Ten));
+        assert_eq!(hand.total().class().to_string(), "Pair10");
+
+        let hand: Hand = "As Ad".parse().unwrap();
+        assert_eq!(hand.total().class().to_string(), "PairA");
+        assert_eq!(hand.total().total(), 12);
+
+        for class in ["Hard16", "Soft18", "Pair8", "PairA"] {
+            assert_eq!(class.parse::<HandClass>().unwrap().to_string(), class);
+        }
+        assert_eq!("PairK".parse::<HandClass>(), Ok(HandClass::Pair(Rank::Ten)));
+        assert!("Pair1".parse::<HandClass>().is_err());
+    }
 }
@@ -0,0 +1,593 @@
+/*!
+
+  A history of the rounds played at a table
+
+*/
+
+use crate::{
+    card::Card,
+    hand::Hand,
+    render::{CardStyle, Renderer},
+    table::{Action, Outcome},
+};
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
+
+/// Something that happened during a round, in the order it happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Step {
+    /// A card was dealt to a seat, or to the dealer when `seat` is [None]
+    Card {
+        /// The seat dealt to
+        seat: Option<usize>,
+        /// The card dealt
+        card: Card,
+    },
+    /// A player at `seat` took `action`
+    Action {
+        /// The seat of the player
+        seat: usize,
+        /// The action taken
+        action: Action,
+    },
+    /// A player at `seat` undid their last `action` in training mode, returning any cards it dealt to the shoe
+    Undo {
+        /// The seat of the player
+        seat: usize,
+        /// The action undone
+        action: Action,
+    },
+    /// A player at `seat` redid an undone `action` in training mode
+    Redo {
+        /// The seat of the player
+        seat: usize,
+        /// The action redone
+        action: Action,
+    },
+}
+
+/// The record of a single seat during a round
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeatRecord {
+    /// The units wagered
+    pub wager: usize,
+    /// The cards of the hand in the order they were dealt
+    pub cards: Vec<Card>,
+    /// The outcome of the hand
+    pub outcome: Outcome,
+    /// The units won (positive) or lost (negative)
+    pub payout: f32,
+}
+
+/// The record of a single round at a table
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundRecord {
+    /// The number of the round at the table, starting from 1
+    pub round: usize,
+    /// The number of cards left in the shoe when the round was dealt
+    pub cards_remaining: usize,
+    /// The penetration of the shoe when the round was dealt
+    pub penetration: f32,
+    /// The running count when the round was dealt, as reported by [Shoe::running_count](crate::Shoe::running_count)
+    pub running_count: f32,
+    /// The true count when the round was dealt
+    pub true_count: f32,
+    /// The record of each seat
+    pub seats: Vec<SeatRecord>,
+    /// The dealer's cards in the order they were dealt
+    pub dealer: Vec<Card>,
+    /// Every card and action in the order they happened
+    pub steps: Vec<Step>,
+}
+
+impl SeatRecord {
+    /// Rebuild the hand of the seat
+    pub fn hand(&self) -> Hand {
+        let mut hand = Hand::default();
+        for &card in &self.cards {
+            hand.insert(card);
+        }
+        hand
+    }
+}
+
+impl RoundRecord {
+    /// Rebuild the hand of the dealer
+    pub fn dealer_hand(&self) -> Hand {
+        let mut hand = Hand::default();
+        for &card in &self.dealer {
+            hand.insert(card);
+        }
+        hand
+    }
+}
+
+/// A bounded buffer of the most recent rounds
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct History {
+    /// The records, oldest first
+    records: VecDeque<RoundRecord>,
+    /// The maximum number of records retained
+    capacity: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::with_capacity(1000)
+    }
+}
+
+impl History {
+    /// Create a history retaining at most `capacity` rounds
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            records: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of rounds retained
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the maximum number of rounds retained, dropping the oldest if needed
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.records.len() > capacity {
+            self.records.pop_front();
+        }
+    }
+
+    /// Add a record, dropping the oldest if the history is full
+    pub fn push(&mut self, record: RoundRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Returns the record of round number `round`, if it is retained
+    pub fn round(&self, round: usize) -> Option<&RoundRecord> {
+        let index = self
+            .records
+            .binary_search_by_key(&round, |record| record.round)
+            .ok()?;
+        self.records.get(index)
+    }
+
+    /// Returns the most recent record
+    pub fn last(&self) -> Option<&RoundRecord> {
+        self.records.back()
+    }
+
+    /// Returns the number of rounds retained
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if no rounds are retained
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Removes all records
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// An iterator over the retained records, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &RoundRecord> {
+        self.records.iter()
+    }
+}
+
+/// The header of the section listing the cards and actions of a round
+const PLAY_HEADER: &str = "*** PLAY ***";
+
+/// The header of the section listing the results of a round
+const SUMMARY_HEADER: &str = "*** SUMMARY ***";
+
+/// The verb written for an action
+fn action_text(action: Action) -> &'static str {
+    match action {
+        Action::Hit => "hits",
+        Action::Stand => "stands",
+        Action::Double => "doubles",
+        Action::Split => "splits",
+        Action::Surrender => "surrenders",
+    }
+}
+
+/// Parses the verb written for an action
+fn parse_action(s: &str) -> Option<Action> {
+    match s {
+        "hits" => Some(Action::Hit),
+        "stands" => Some(Action::Stand),
+        "doubles" => Some(Action::Double),
+        "splits" => Some(Action::Split),
+        "surrenders" => Some(Action::Surrender),
+        _ => None,
+    }
+}
+
+/// Writes the cards and value of a hand
+fn hand_text(cards: &[Card], hand: &Hand) -> String {
+    let ascii = Renderer::new(CardStyle::Ascii);
+    let cards: Vec<String> = cards.iter().map(|&c| ascii.card(c)).collect();
+    let value = if hand.blackjack() {
+        "Blackjack".to_string()
+    } else {
+        match hand.value() {
+            Some(v) => v.to_string(),
+            None => "Bust".to_string(),
+        }
+    };
+    format!("{} ({})", cards.join(" "), value)
+}
+
+/// Writes who a line refers to
+fn seat_text(seat: Option<usize>) -> String {
+    match seat {
+        Some(s) => format!("Seat {}", s + 1),
+        None => "Dealer".to_string(),
+    }
+}
+
+/// Writes the round in a human-readable hand history format, such as:
+///
+/// ```text
+/// Round #3
+/// Shoe: 301 cards, penetration 0.03525641, running count 0.16666667, true count 1.0365449
+/// Seat 1: wager 25
+/// *** PLAY ***
+/// Seat 1: 7s
+/// Dealer: 9d
+/// Seat 1: 5h
+/// Dealer: 7c
+/// Seat 1: hits
+/// Seat 1: 4c
+/// Seat 1: stands
+/// Dealer: Tc
+/// *** SUMMARY ***
+/// Dealer: 9d 7c Tc (Bust)
+/// Seat 1: 7s 5h 4c (16) Win +25
+/// ```
+impl Display for RoundRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Round #{}", self.round)?;
+        writeln!(
+            f,
+            "Shoe: {} cards, penetration {}, running count {}, true count {}",
+            self.cards_remaining, self.penetration, self.running_count, self.true_count
+        )?;
+        for (i, seat) in self.seats.iter().enumerate() {
+            writeln!(f, "{}: wager {}", seat_text(Some(i)), seat.wager)?;
+        }
+        writeln!(f, "{PLAY_HEADER}")?;
+        for step in &self.steps {
+            match *step {
+                Step::Card { seat, card } => {
+                    let ascii = Renderer::new(CardStyle::Ascii);
+                    writeln!(f, "{}: {}", seat_text(seat), ascii.card(card))?
+                }
+                Step::Action { seat, action } => {
+                    writeln!(f, "{}: {}", seat_text(Some(seat)), action_text(action))?
+                }
+                Step::Undo { seat, action } => writeln!(
+                    f,
+                    "{}: undoes {}",
+                    seat_text(Some(seat)),
+                    action_text(action)
+                )?,
+                Step::Redo { seat, action } => writeln!(
+                    f,
+                    "{}: redoes {}",
+                    seat_text(Some(seat)),
+                    action_text(action)
+                )?,
+            }
+        }
+        writeln!(f, "{SUMMARY_HEADER}")?;
+        writeln!(
+            f,
+            "Dealer: {}",
+            hand_text(&self.dealer, &self.dealer_hand())
+        )?;
+        for (i, seat) in self.seats.iter().enumerate() {
+            writeln!(
+                f,
+                "{}: {} {:?} {:+}",
+                seat_text(Some(i)),
+                hand_text(&seat.cards, &seat.hand()),
+                seat.outcome,
+                seat.payout
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An error parsing a hand history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHistoryError {
+    /// The line number the error occurred on, starting from 1
+    pub line: usize,
+    /// A description of the error
+    pub message: String,
+}
+
+impl Display for ParseHistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseHistoryError {}
+
+/// A cursor over the non-empty lines of a hand history
+struct Lines<'a> {
+    /// The lines with their line numbers
+    lines: Vec<(usize, &'a str)>,
+    /// The index of the next line
+    next: usize,
+    /// The number of the last line read
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    /// Create a cursor over `s`
+    fn new(s: &'a str) -> Self {
+        Self {
+            lines: s
+                .lines()
+                .enumerate()
+                .map(|(i, l)| (i + 1, l.trim()))
+                .filter(|(_, l)| !l.is_empty())
+                .collect(),
+            next: 0,
+            line: 0,
+        }
+    }
+
+    /// Returns true if all lines have been read
+    fn is_empty(&self) -> bool {
+        self.next == self.lines.len()
+    }
+
+    /// An error at the current line
+    fn error(&self, message: impl Into<String>) -> ParseHistoryError {
+        ParseHistoryError {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the next line
+    fn next(&mut self) -> Result<&'a str, ParseHistoryError> {
+        match self.lines.get(self.next) {
+            Some(&(n, l)) => {
+                self.next += 1;
+                self.line = n;
+                Ok(l)
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// Returns the next line, if it is not `header`
+    fn next_until(&mut self, header: &str) -> Result<Option<&'a str>, ParseHistoryError> {
+        match self.lines.get(self.next) {
+            Some((_, l)) if *l == header => Ok(None),
+            _ => self.next().map(Some),
+        }
+    }
+
+    /// Consumes the next line, which must be `expected`
+    fn expect(&mut self, expected: &str) -> Result<(), ParseHistoryError> {
+        let line = self.next()?;
+        if line == expected {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{expected}`, found `{line}`")))
+        }
+    }
+
+    /// Parses `s` as a `T`, naming it `what` in errors
+    fn parse<T: FromStr>(&self, s: &str, what: &str) -> Result<T, ParseHistoryError> {
+        s.trim()
+            .parse()
+            .map_err(|_| self.error(format!("invalid {what} `{s}`")))
+    }
+
+    /// Strips `prefix` and `suffix` from a comma-separated field
+    fn field(
+        &self,
+        field: &'a str,
+        prefix: &str,
+        suffix: &str,
+    ) -> Result<&'a str, ParseHistoryError> {
+        field
+            .strip_prefix(prefix)
+            .and_then(|f| f.strip_suffix(suffix))
+            .ok_or_else(|| self.error(format!("expected `{prefix}{suffix}`, found `{field}`")))
+    }
+
+    /// Splits a line into who it refers to and the rest
+    fn seat(&self, line: &'a str) -> Result<(Option<usize>, &'a str), ParseHistoryError> {
+        let (who, rest) = line.split_once(':').ok_or_else(|| {
+            self.error(format!("expected `Seat N:` or `Dealer:`, found `{line}`"))
+        })?;
+        let rest = rest.trim();
+        if who == "Dealer" {
+            return Ok((None, rest));
+        }
+        match who.strip_prefix("Seat ") {
+            Some(n) => match self.parse::<usize>(n, "seat")? {
+                0 => Err(self.error("seats are numbered from 1")),
+                n => Ok((Some(n - 1), rest)),
+            },
+            None => Err(self.error(format!("expected `Seat N` or `Dealer`, found `{who}`"))),
+        }
+    }
+}
+
+/// Parses a single round written by [RoundRecord]'s [Display] implementation
+impl FromStr for RoundRecord {
+    type Err = ParseHistoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rounds = from_text(s)?;
+        match rounds.len() {
+            1 => Ok(rounds.remove(0)),
+            n => Err(ParseHistoryError {
+                line: 1,
+                message: format!("expected one round, found {n}"),
+            }),
+        }
+    }
+}
+
+/// Parses one round from `lines`
+fn parse_round(lines: &mut Lines<'_>) -> Result<RoundRecord, ParseHistoryError> {
+    let mut record = RoundRecord::default();
+
+    let line = lines.next()?;
+    let round = line
+        .strip_prefix("Round #")
+        .ok_or_else(|| lines.error(format!("expected `Round #N`, found `{line}`")))?;
+    record.round = lines.parse(round, "round number")?;
+
+    let line = lines.next()?;
+    let shoe = line
+        .strip_prefix("Shoe: ")
+        .ok_or_else(|| lines.error(format!("expected `Shoe:`, found `{line}`")))?;
+    let fields: Vec<&str> = shoe.split(", ").collect();
+    let [cards, penetration, running, true_count] = fields.as_slice() else {
+        return Err(lines.error(format!("expected four shoe fields, found `{shoe}`")));
+    };
+    record.cards_remaining = lines.parse(lines.field(cards, "", " cards")?, "card count")?;
+    record.penetration =
+        lines.parse(lines.field(penetration, "penetration ", "")?, "penetration")?;
+    record.running_count =
+        lines.parse(lines.field(running, "running count ", "")?, "running count")?;
+    record.true_count = lines.parse(lines.field(true_count, "true count ", "")?, "true count")?;
+
+    let mut wagers = Vec::new();
+    while let Some(line) = lines.next_until(PLAY_HEADER)? {
+        let (seat, rest) = lines.seat(line)?;
+        if seat != Some(wagers.len()) {
+            return Err(lines.error(format!("expected the wager of seat {}", wagers.len() + 1)));
+        }
+        let wager = rest
+            .strip_prefix("wager ")
+            .ok_or_else(|| lines.error(format!("expected `wager`, found `{rest}`")))?;
+        wagers.push(lines.parse::<usize>(wager, "wager")?);
+    }
+
+    lines.expect(PLAY_HEADER)?;
+    let mut cards = vec![Vec::new(); wagers.len()];
+    // The number of cards each undoable action's seat held before it
+    let mut actions = Vec::new();
+    while let Some(line) = lines.next_until(SUMMARY_HEADER)? {
+        let (seat, rest) = lines.seat(line)?;
+        if seat.is_some_and(|s| s >= wagers.len()) {
+            return Err(lines.error(format!("no wager for `{line}`")));
+        }
+        let step = if let Ok(card) = rest.parse::<Card>() {
+            match seat {
+                Some(s) => cards[s].push(card),
+                None => record.dealer.push(card),
+            }
+            Step::Card { seat, card }
+        } else if let (Some(seat), Some(action)) = (seat, parse_action(rest)) {
+            actions.push((seat, cards[seat].len()));
+            Step::Action { seat, action }
+        } else if let (Some(seat), Some(action)) =
+            (seat, rest.strip_prefix("undoes ").and_then(parse_action))
+        {
+            match actions.pop() {
+                Some((s, len)) if s == seat => cards[seat].truncate(len),
+                _ => return Err(lines.error(format!("nothing for seat {} to undo", seat + 1))),
+            }
+            Step::Undo { seat, action }
+        } else if let (Some(seat), Some(action)) =
+            (seat, rest.strip_prefix("redoes ").and_then(parse_action))
+        {
+            actions.push((seat, cards[seat].len()));
+            Step::Redo { seat, action }
+        } else {
+            return Err(lines.error(format!("expected a card or action, found `{rest}`")));
+        };
+        record.steps.push(step);
+    }
+
+    lines.expect(SUMMARY_HEADER)?;
+    let line = lines.next()?;
+    if lines.seat(line)?.0.is_some() {
+        return Err(lines.error(format!("expected `Dealer:`, found `{line}`")));
+    }
+    for (i, (wager, cards)) in wagers.into_iter().zip(cards).enumerate() {
+        let line = lines.next()?;
+        let (seat, rest) = lines.seat(line)?;
+        if seat != Some(i) {
+            return Err(lines.error(format!("expected the summary of seat {}", i + 1)));
+        }
+        let mut tokens = rest.rsplitn(3, ' ');
+        let (Some(payout), Some(outcome)) = (tokens.next(), tokens.next()) else {
+            return Err(lines.error(format!("expected an outcome and payout, found `{rest}`")));
+        };
+        let outcome = match outcome {
+            "Blackjack" => Outcome::Blackjack,
+            "Win" => Outcome::Win,
+            "Lose" => Outcome::Lose,
+            "Push" => Outcome::Push,
+            _ => return Err(lines.error(format!("invalid outcome `{outcome}`"))),
+        };
+        record.seats.push(SeatRecord {
+            wager,
+            cards,
+            outcome,
+            payout: lines.parse(payout, "payout")?,
+        });
+    }
+
+    Ok(record)
+}
+
+/// Writes `records` in the hand history format of [RoundRecord]'s [Display] implementation, separated by blank lines
+pub fn to_text<'a>(records: impl IntoIterator<Item = &'a RoundRecord>) -> String {
+    let rounds: Vec<String> = records.into_iter().map(|r| r.to_string()).collect();
+    rounds.join("\n")
+}
+
+/// Parses the rounds of a hand history written by [to_text]
+pub fn from_text(s: &str) -> Result<Vec<RoundRecord>, ParseHistoryError> {
+    let mut lines = Lines::new(s);
+    let mut records = Vec::new();
+    while !lines.is_empty() {
+        records.push(parse_round(&mut lines)?);
+    }
+    Ok(records)
+}
+
+/// Writes `records` as a JSON array
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn to_json<'a>(records: impl IntoIterator<Item = &'a RoundRecord>) -> String {
+    let records: Vec<&RoundRecord> = records.into_iter().collect();
+    serde_json::to_string_pretty(&records).expect("records serialize to JSON")
+}
+
+/// Parses the rounds of a JSON array written by [to_json]
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn from_json(s: &str) -> Result<Vec<RoundRecord>, serde_json::Error> {
+    serde_json::from_str(s)
+}
@@ -8,12 +8,29 @@ Library for blackjack games and trainers.
 
 */
 mod bet;
+pub mod betting;
 mod card;
+mod composition;
+pub mod dealer;
+mod deck;
+pub mod ev;
+mod event;
 mod hand;
+pub mod history;
+pub mod metrics;
+pub mod render;
+pub mod replay;
 mod shoe;
+pub mod shuffle;
+pub mod sim;
+pub mod strategy;
 pub mod table;
+pub mod tracking;
 
 pub use bet::*;
 pub use card::*;
+pub use composition::*;
+pub use deck::*;
+pub use event::*;
 pub use hand::*;
 pub use shoe::*;
@@ -0,0 +1,134 @@
+/*!
+
+  Performance metrics for card counters
+
+  The metrics of a game are derived from the expected units won per round and its standard
+  deviation, either measured by a [Simulator] or calculated by hand. [Metrics::simulate]
+  plays a configured simulator, so the metrics reflect its rules, its [BettingStrategy] and
+  the [Counter] its bets are sized by.
+
+*/
+
+use crate::{
+    betting::BettingStrategy,
+    shoe::Counter,
+    sim::{SimStats, Simulator},
+    strategy::PlayingStrategy,
+};
+
+/// Performance metrics for playing a game with a given counting system and bet ramp
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metrics {
+    /// The expected units won per round
+    ev: f64,
+    /// The standard deviation of the units won per round
+    std_dev: f64,
+    /// The number of rounds played per hour
+    hands_per_hour: f64,
+}
+
+impl Metrics {
+    /// Metrics of a game winning `ev` units per round with a standard deviation of `std_dev`
+    /// units per round, at 100 rounds per hour
+    pub fn new(ev: f64, std_dev: f64) -> Self {
+        Self {
+            ev,
+            std_dev,
+            hands_per_hour: 100.0,
+        }
+    }
+
+    /// Metrics of simulated rounds
+    pub fn from_stats(stats: &SimStats) -> Self {
+        Self::new(stats.ev(), stats.std_dev())
+    }
+
+    /// Metrics of playing `rounds` rounds with `sim`
+    pub fn simulate<P: PlayingStrategy, B: BettingStrategy, C: Counter>(
+        sim: &mut Simulator<P, B, C>,
+        rounds: u64,
+    ) -> Self {
+        Self::from_stats(&sim.play_rounds(rounds))
+    }
+
+    /// Play `hands_per_hour` rounds per hour instead
+    pub fn hands_per_hour(mut self, hands_per_hour: f64) -> Self {
+        self.hands_per_hour = hands_per_hour;
+        self
+    }
+
+    /// Returns the expected units won per round
+    pub fn ev(&self) -> f64 {
+        self.ev
+    }
+
+    /// Returns the standard deviation of the units won per round
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    /// Returns the desirability index, 1000 times the ratio of the expected win to the standard
+    /// deviation. The square root of [score](Self::score). `None` without any variance.
+    pub fn di(&self) -> Option<f64> {
+        (self.std_dev > 0.0).then(|| 1000.0 * self.ev / self.std_dev)
+    }
+
+    /// Returns SCORE, the expected units won per 100 rounds betting at the Kelly optimal
+    /// fraction of a bankroll of 10,000 units, the same as per round with a bankroll of one
+    /// million units: 1,000,000 times (ev / sd)². `None` without any variance.
+    pub fn score(&self) -> Option<f64> {
+        (self.std_dev > 0.0).then(|| 1_000_000.0 * (self.ev / self.std_dev).powi(2))
+    }
+
+    /// Returns N0, the number of rounds after which the expected win equals one standard
+    /// deviation of the result: (sd / ev)². `None` without an expected win or loss, as the
+    /// result never leaves its standard deviation.
+    pub fn n0(&self) -> Option<f64> {
+        (self.ev != 0.0).then(|| (self.std_dev / self.ev).powi(2))
+    }
+
+    /// Returns the number of hours to play [n0](Self::n0) rounds
+    pub fn hours_to_n0(&self) -> Option<f64> {
+        self.n0().map(|n0| n0 / self.hands_per_hour)
+    }
+
+    /// Returns the expected units won per hour
+    pub fn win_per_hour(&self) -> f64 {
+        self.ev * self.hands_per_hour
+    }
+
+    /// Returns the standard deviation of the units won per hour
+    pub fn std_dev_per_hour(&self) -> f64 {
+        self.std_dev * self.hands_per_hour.sqrt()
+    }
+
+    /// Returns the probability of ever losing a bankroll of `bankroll` units, playing forever
+    /// without resizing bets: exp(-2 · ev · bankroll / sd²). A game without an edge is
+    /// eventually ruinous.
+    pub fn risk_of_ruin(&self, bankroll: f64) -> f64 {
+        if self.ev <= 0.0 {
+            1.0
+        } else {
+            (-2.0 * self.ev * bankroll / self.std_dev.powi(2))
+                .exp()
+                .min(1.0)
+        }
+    }
+
+    /// Returns the bankroll in units needed for a risk of ruin of `risk`, or infinity
+    /// for a game without an edge
+    pub fn bankroll_for_risk(&self, risk: f64) -> f64 {
+        if self.ev <= 0.0 {
+            f64::INFINITY
+        } else {
+            -self.std_dev.powi(2) * risk.ln() / (2.0 * self.ev)
+        }
+    }
+}
+
+impl From<&SimStats> for Metrics {
+    fn from(stats: &SimStats) -> Self {
+        Self::from_stats(stats)
+    }
+}
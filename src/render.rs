@@ -0,0 +1,170 @@
+/*!
+
+  Rendering cards, hands and tables as text
+
+*/
+
+use crate::{
+    card::{Card, Rank, Suit},
+    hand::Hand,
+    table::Table,
+};
+
+/// The way cards are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CardStyle {
+    /// Plain ASCII, such as `As` or `Td`
+    Ascii,
+    /// Outline suit symbols, such as `A♤`, as printed by [Display](std::fmt::Display)
+    #[default]
+    Outline,
+    /// Filled suit symbols, such as `A♠`
+    Filled,
+    /// A single Unicode playing card glyph, such as `🂡`
+    Unicode,
+    /// Multi-line ASCII art cards, drawn side by side
+    AsciiArt,
+}
+
+/// Renders cards, hands and tables in a [CardStyle], optionally with ANSI colored suits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Renderer {
+    /// The way cards are drawn
+    style: CardStyle,
+    /// Whether red suits are colored red with ANSI escape codes
+    color: bool,
+}
+
+/// The ANSI escape code for red text
+const RED: &str = "\x1b[31m";
+/// The ANSI escape code for the default text color
+const RESET: &str = "\x1b[0m";
+
+impl Renderer {
+    /// Create a renderer drawing cards in `style`, without color
+    pub fn new(style: CardStyle) -> Self {
+        Self {
+            style,
+            color: false,
+        }
+    }
+
+    /// Color hearts and diamonds red and leave clubs and spades in the terminal's default color
+    pub fn colored(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the style cards are drawn in
+    pub fn style(&self) -> CardStyle {
+        self.style
+    }
+
+    /// Returns the lines of `card`. Every style is a single line except [CardStyle::AsciiArt].
+    fn card_lines(&self, card: Card) -> Vec<String> {
+        let rank = card.rank();
+        let suit = card.suit();
+        let lines = match self.style {
+            CardStyle::Ascii => vec![format!("{}{}", rank.letter(), suit.letter())],
+            CardStyle::Outline => vec![card.to_string()],
+            CardStyle::Filled => vec![format!("{rank}{}", suit.filled())],
+            CardStyle::Unicode => vec![glyph(card).to_string()],
+            CardStyle::AsciiArt => vec![
+                "+-----+".to_string(),
+                format!("|{rank:<2}   |"),
+                format!("|  {}  |", suit.letter()),
+                format!("|   {rank:>2}|"),
+                "+-----+".to_string(),
+            ],
+        };
+        if self.color && matches!(suit, Suit::Heart | Suit::Diamond) {
+            lines
+                .into_iter()
+                .map(|l| format!("{RED}{l}{RESET}"))
+                .collect()
+        } else {
+            lines
+        }
+    }
+
+    /// Returns the lines of `cards` drawn side by side
+    fn cards_lines(&self, cards: &[Card]) -> Vec<String> {
+        if self.style != CardStyle::AsciiArt {
+            let cards: Vec<String> = cards.iter().map(|&c| self.card(c)).collect();
+            return vec![format!("[{}]", cards.join(", "))];
+        }
+        let drawn: Vec<Vec<String>> = cards.iter().map(|&c| self.card_lines(c)).collect();
+        (0..5)
+            .map(|i| {
+                let line: Vec<&str> = drawn.iter().map(|lines| lines[i].as_str()).collect();
+                line.join(" ")
+            })
+            .collect()
+    }
+
+    /// Render `card`
+    pub fn card(&self, card: Card) -> String {
+        self.card_lines(card).join("\n")
+    }
+
+    /// Render `hand`, followed by its value
+    pub fn hand(&self, hand: &Hand) -> String {
+        let mut lines = self.cards_lines(hand.cards());
+        let last = lines.len() - 1;
+        lines[last] = format!("{} {}", lines[last], hand.summary());
+        lines.join("\n")
+    }
+
+    /// Render `table`: the dealer's hand, the count and penetration, and every player's hand
+    pub fn table(&self, table: &Table) -> String {
+        let dealer = self.hand(table.dealer_hand());
+        let mut s = format!(
+            "\n\n\nDealer: {}    {} {}\n",
+            self.indent(dealer),
+            table.shoe().running_count(),
+            table.shoe().penetration()
+        );
+        for (i, player) in table.player_hands().enumerate() {
+            s += &format!("Player {}: {}\n", i + 1, self.indent(self.hand(player)));
+        }
+        s
+    }
+
+    /// Starts multi-line renders on a new line, so that the cards line up
+    fn indent(&self, rendered: String) -> String {
+        if rendered.contains('\n') {
+            format!("\n{rendered}")
+        } else {
+            rendered
+        }
+    }
+}
+
+/// Returns the Unicode playing card glyph of `card`, from the Playing Cards block (U+1F0A0)
+fn glyph(card: Card) -> char {
+    let suit = match card.suit() {
+        Suit::Spade => 0x1F0A0,
+        Suit::Heart => 0x1F0B0,
+        Suit::Diamond => 0x1F0C0,
+        Suit::Club => 0x1F0D0,
+    };
+    // The knight (0xC) between the jack and the queen is skipped
+    let rank = match card.rank() {
+        Rank::Ace => 0x1,
+        Rank::Two => 0x2,
+        Rank::Three => 0x3,
+        Rank::Four => 0x4,
+        Rank::Five => 0x5,
+        Rank::Six => 0x6,
+        Rank::Seven => 0x7,
+        Rank::Eight => 0x8,
+        Rank::Nine => 0x9,
+        Rank::Ten => 0xA,
+        Rank::Jack => 0xB,
+        Rank::Queen => 0xD,
+        Rank::King => 0xE,
+    };
+    char::from_u32(suit + rank).expect("playing card code points are valid")
+}
@@ -0,0 +1,449 @@
+/*!
+
+  Step-through replay of recorded rounds
+
+*/
+
+use crate::{
+    bet::Bet,
+    card::Card,
+    hand::Hand,
+    history::{RoundRecord, Step},
+    shoe::ShoeBuilder,
+    strategy::{BasicStrategy, PlayingStrategy},
+    table::{Action, Table},
+};
+use std::fmt::Display;
+
+/// A single move of a replay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Move {
+    /// Bets are placed and the initial cards are dealt
+    Deal {
+        /// The number of the round being dealt
+        round: usize,
+    },
+    /// A player takes an action
+    Action {
+        /// The seat of the player
+        seat: usize,
+        /// The action taken
+        action: Action,
+    },
+    /// A player undoes their last action in training mode
+    Undo {
+        /// The seat of the player
+        seat: usize,
+    },
+    /// A player redoes their last undone action in training mode
+    Redo {
+        /// The seat of the player
+        seat: usize,
+    },
+    /// The dealer takes a card
+    DealerHit,
+    /// The dealer's hole card is flipped and the round is settled
+    Settle,
+}
+
+/// A decision made by a player, with the count at the time and the recommended action
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Decision {
+    /// The number of the round
+    pub round: usize,
+    /// The seat of the player
+    pub seat: usize,
+    /// The player's hand before the decision
+    pub hand: Hand,
+    /// The dealer's face up card
+    pub upcard: Card,
+    /// The running count, as reported by [Shoe::running_count](crate::Shoe::running_count)
+    pub running_count: f32,
+    /// The true count
+    pub true_count: f32,
+    /// The action the player took
+    pub taken: Action,
+    /// The action the strategy recommends
+    pub recommended: Action,
+}
+
+impl Decision {
+    /// Returns true if the player did not take the recommended action
+    pub fn is_mistake(&self) -> bool {
+        self.taken != self.recommended
+    }
+}
+
+/// An error preparing a replay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The cards dealt in round `round` do not follow the shoe order
+    MismatchedOrder {
+        /// The number of the round
+        round: usize,
+    },
+    /// Round `round` has a different number of seats than the first round
+    MismatchedSeats {
+        /// The number of the round
+        round: usize,
+    },
+    /// Round `round` undoes an action that was never taken
+    UnmatchedUndo {
+        /// The number of the round
+        round: usize,
+    },
+    /// Round `round` has an action the table can not replay
+    UnsupportedAction {
+        /// The number of the round
+        round: usize,
+        /// The action
+        action: Action,
+    },
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MismatchedOrder { round } => {
+                write!(f, "round {round} does not follow the shoe order")
+            }
+            Self::MismatchedSeats { round } => {
+                write!(f, "round {round} has a different number of seats")
+            }
+            Self::UnmatchedUndo { round } => {
+                write!(f, "round {round} undoes an action that was never taken")
+            }
+            Self::UnsupportedAction { round, action } => {
+                write!(f, "round {round} has an unsupported action {action}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Replays recorded rounds on a [Table] one [Move] at a time, forwards and backwards
+pub struct Replayer<S = BasicStrategy> {
+    /// The rounds being replayed
+    records: Vec<RoundRecord>,
+    /// The order of the shoe, first card dealt first
+    order: Vec<Card>,
+    /// The strategy making recommendations
+    strategy: S,
+    /// The moves of the replay
+    moves: Vec<Move>,
+    /// The number of moves applied to the table
+    position: usize,
+    /// The table the moves are applied to
+    table: Table,
+}
+
+impl Replayer<BasicStrategy> {
+    /// Prepare a replay of `records` dealt from a shoe in `order` (first card dealt first),
+    /// recommending actions with [BasicStrategy]
+    pub fn new(records: Vec<RoundRecord>, order: Vec<Card>) -> Result<Self, ReplayError> {
+        let seats = records.first().map_or(0, |r| r.seats.len());
+        let mut moves = Vec::new();
+        let (mut dealt, mut position) = (Vec::new(), 0);
+        for record in &records {
+            if record.seats.len() != seats {
+                return Err(ReplayError::MismatchedSeats {
+                    round: record.round,
+                });
+            }
+            Self::deal_order(record, &mut dealt, &mut position)?;
+            if !order.starts_with(&dealt) {
+                return Err(ReplayError::MismatchedOrder {
+                    round: record.round,
+                });
+            }
+            moves.extend(Self::moves(record)?);
+        }
+
+        let table = Self::fresh_table(&order, seats);
+        Ok(Self {
+            records,
+            order,
+            strategy: BasicStrategy::default(),
+            moves,
+            position: 0,
+            table,
+        })
+    }
+
+    /// Prepare a replay of `records`, taking the shoe order from the cards dealt in the records
+    pub fn from_records(records: Vec<RoundRecord>) -> Result<Self, ReplayError> {
+        let (mut order, mut dealt) = (Vec::new(), 0);
+        for record in &records {
+            Self::deal_order(record, &mut order, &mut dealt)?;
+        }
+        Self::new(records, order)
+    }
+
+    /// Extend `order` with the cards `record` takes from the shoe, where `dealt` is the
+    /// number of cards of `order` dealt so far. Cards returned to the shoe by an undo are
+    /// dealt again from the same position of the order.
+    fn deal_order(
+        record: &RoundRecord,
+        order: &mut Vec<Card>,
+        dealt: &mut usize,
+    ) -> Result<(), ReplayError> {
+        let mut actions = Vec::new();
+        for step in &record.steps {
+            match *step {
+                Step::Card { card, .. } => {
+                    match order.get(*dealt) {
+                        None => order.push(card),
+                        Some(&c) if c == card => {}
+                        Some(_) => {
+                            return Err(ReplayError::MismatchedOrder {
+                                round: record.round,
+                            });
+                        }
+                    }
+                    *dealt += 1;
+                }
+                Step::Action { .. } | Step::Redo { .. } => actions.push(*dealt),
+                Step::Undo { .. } => {
+                    *dealt = actions.pop().ok_or(ReplayError::UnmatchedUndo {
+                        round: record.round,
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the moves that replay `record`
+    fn moves(record: &RoundRecord) -> Result<Vec<Move>, ReplayError> {
+        let initial = 2 * (record.seats.len() + 1);
+        let mut moves = vec![Move::Deal {
+            round: record.round,
+        }];
+        let mut cards = 0;
+        for step in &record.steps {
+            match *step {
+                Step::Card { seat, .. } => {
+                    cards += 1;
+                    if seat.is_none() && cards > initial {
+                        moves.push(Move::DealerHit);
+                    }
+                }
+                Step::Action { seat, action } => match action {
+                    action if Table::SUPPORTED_ACTIONS.contains(&action) => {
+                        moves.push(Move::Action { seat, action })
+                    }
+                    _ => {
+                        return Err(ReplayError::UnsupportedAction {
+                            round: record.round,
+                            action,
+                        });
+                    }
+                },
+                Step::Undo { seat, .. } => moves.push(Move::Undo { seat }),
+                Step::Redo { seat, .. } => moves.push(Move::Redo { seat }),
+            }
+        }
+        moves.push(Move::Settle);
+        Ok(moves)
+    }
+}
+
+/// Returns the running count, as reported by [Shoe::running_count](crate::Shoe::running_count),
+/// and the true count of the shoe `record` was dealt from, once the cards `dealt` since the
+/// round started are out. The replayed shoe only holds the recorded cards, so the counts are
+/// carried on from the ones recorded when the round was dealt.
+fn counts(record: &RoundRecord, dealt: impl Iterator<Item = Card>) -> (f32, f32) {
+    // The shoe counts as one deck per 52 cards it is loaded with
+    let capacity = (record.cards_remaining as f32 / (1.0 - record.penetration)).round();
+    let decks = (capacity as usize).div_ceil(52).max(1) as f32;
+    let mut running = (record.running_count * decks).round();
+    let mut remaining = record.cards_remaining;
+    for card in dealt {
+        running += card.count() as f32;
+        remaining = remaining.saturating_sub(1);
+    }
+    let true_count = if remaining == 0 {
+        0.0
+    } else {
+        running / (remaining as f32 / 52.0)
+    };
+    (running / decks, true_count)
+}
+
+impl<S: PlayingStrategy> Replayer<S> {
+    /// Recommend actions with `strategy` instead
+    pub fn with_strategy<T: PlayingStrategy>(self, strategy: T) -> Replayer<T> {
+        Replayer {
+            records: self.records,
+            order: self.order,
+            strategy,
+            moves: self.moves,
+            position: self.position,
+            table: self.table,
+        }
+    }
+
+    /// Create a fresh table dealing from a shoe stacked in `order`
+    fn fresh_table(order: &[Card], seats: usize) -> Table {
+        let shoe = ShoeBuilder::new()
+            .cards(order.iter().copied())
+            .stacked()
+            .build();
+        let mut table = Table::with_shoe(shoe, seats, 1.0);
+        table.set_training(true);
+        table
+    }
+
+    /// Apply `mv` to `table`
+    fn apply(records: &[RoundRecord], table: &mut Table, mv: Move) {
+        match mv {
+            Move::Deal { round } => {
+                let record = records.iter().find(|r| r.round == round).unwrap();
+                for (seat, s) in record.seats.iter().enumerate() {
+                    table.place_bet(seat, Bet::from_units(s.wager));
+                }
+                table.deal();
+            }
+            Move::Action { seat, action } => match action {
+                Action::Hit => {
+                    table.player_hit(seat);
+                }
+                Action::Stand => table.player_stand(seat),
+                _ => unreachable!("unsupported actions are rejected by the replayer"),
+            },
+            Move::Undo { .. } => {
+                table.undo();
+            }
+            Move::Redo { .. } => {
+                table.redo();
+            }
+            Move::DealerHit => {
+                table.dealer_hit();
+            }
+            Move::Settle => {
+                table.flip_hole();
+                table.clear_hands();
+            }
+        }
+    }
+
+    /// Returns the decision `mv` makes in round number `round` against `table`, if it is a player action
+    fn decision_at(&self, round: usize, table: &Table, mv: Move) -> Option<Decision> {
+        let Move::Action { seat, action } = mv else {
+            return None;
+        };
+        let hand = table.player_hand(seat).clone();
+        let upcard = table.dealer_upcard()?;
+        let allowed = table.allowed_actions(seat);
+        let record = self.records.iter().find(|r| r.round == round)?;
+        let dealt = table
+            .player_hands()
+            .chain([table.dealer_hand()])
+            .flat_map(|hand| hand.cards().iter().copied());
+        let (running_count, true_count) = counts(record, dealt);
+        Some(Decision {
+            round,
+            seat,
+            recommended: self.strategy.action(&hand, upcard, &allowed),
+            hand,
+            upcard,
+            running_count,
+            true_count,
+            taken: action,
+        })
+    }
+
+    /// Returns the number of moves in the replay
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Returns true if there are no moves to replay
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Returns the number of moves applied so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the table as of the current position
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// Returns the next move to apply
+    pub fn next_move(&self) -> Option<Move> {
+        self.moves.get(self.position).copied()
+    }
+
+    /// Returns the decision the next move makes, if it is a player action
+    pub fn decision(&self) -> Option<Decision> {
+        let round = self.moves[..self.position]
+            .iter()
+            .rev()
+            .find_map(|mv| match mv {
+                Move::Deal { round } => Some(*round),
+                _ => None,
+            })?;
+        self.decision_at(round, &self.table, self.next_move()?)
+    }
+
+    /// Apply the next move. Returns the move, or [None] at the end of the replay.
+    pub fn forward(&mut self) -> Option<Move> {
+        let mv = self.next_move()?;
+        Self::apply(&self.records, &mut self.table, mv);
+        self.position += 1;
+        Some(mv)
+    }
+
+    /// Undo the last move. Returns false at the start of the replay.
+    pub fn backward(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.seek(self.position - 1);
+        true
+    }
+
+    /// Move to `position`, the number of moves applied
+    pub fn seek(&mut self, position: usize) {
+        let position = position.min(self.moves.len());
+        if position < self.position {
+            let seats = self.records.first().map_or(0, |r| r.seats.len());
+            self.table = Self::fresh_table(&self.order, seats);
+            self.position = 0;
+        }
+        while self.position < position {
+            self.forward();
+        }
+    }
+
+    /// Returns every decision of the replay, in order
+    pub fn decisions(&self) -> Vec<Decision> {
+        let seats = self.records.first().map_or(0, |r| r.seats.len());
+        let mut table = Self::fresh_table(&self.order, seats);
+        let mut decisions = Vec::new();
+        let mut round = 0;
+        for &mv in &self.moves {
+            if let Move::Deal { round: r } = mv {
+                round = r;
+            }
+            decisions.extend(self.decision_at(round, &table, mv));
+            Self::apply(&self.records, &mut table, mv);
+        }
+        decisions
+    }
+
+    /// Returns every decision where the player did not take the recommended action
+    pub fn mistakes(&self) -> Vec<Decision> {
+        self.decisions()
+            .into_iter()
+            .filter(Decision::is_mistake)
+            .collect()
+    }
+}
@@ -4,9 +4,14 @@
 
 */
 
-use crate::card::{Card, Rank, Suit};
-use rand::{rng, seq::SliceRandom};
-use std::ops::Index;
+use crate::{
+    card::{Card, Rank, Suit},
+    event::{Event, Observer},
+    shuffle::ShuffleProcedure,
+};
+use rand::{SeedableRng, rng, seq::SliceRandom};
+use rand_chacha::ChaCha12Rng;
+use std::{collections::BTreeMap, ops::Index};
 
 /// A trait for card counting strategies
 pub trait Counter {
@@ -28,9 +33,20 @@ pub trait Counter {
             self.insert(card);
         }
     }
+
+    /// Returns the tag of `rank`: how much dealing a card of `rank` adds to the running count
+    fn tag(rank: Rank) -> f32;
+}
+
+/// A card counting strategy that can undo the count of a card returned to the shoe
+pub trait UndoCounter: Counter {
+    /// Undo the count of a card returned to the shoe
+    fn remove(&mut self, card: Card);
 }
 
 /// The high-low card counting strategy
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HiLoCounter {
     running_count: i32,
     num_decks: usize,
@@ -55,51 +71,171 @@ impl Counter for HiLoCounter {
     fn insert(&mut self, card: Card) {
         self.running_count += card.count() as i32;
     }
+
+    fn tag(rank: Rank) -> f32 {
+        Card::new(Suit::Spade, rank).count() as f32
+    }
 }
 
-/// A shoe of cards
+impl UndoCounter for HiLoCounter {
+    fn remove(&mut self, card: Card) {
+        self.running_count -= card.count() as i32;
+    }
+}
+
+impl HiLoCounter {
+    /// Get the running count, unadjusted for the number of decks
+    pub fn running(&self) -> i32 {
+        self.running_count
+    }
+}
+
+/// A shoe of cards.
+///
+/// With the `serde` feature, a shoe serializes with its order and the state of its
+/// random number generator, so later shuffles are reproduced after deserializing.
+/// Subscribed observers are not serialized.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shoe {
     /// the cards
     cards: Vec<Card>,
+    /// The cards the shoe is loaded with when full
+    composition: Vec<Card>,
+    /// The cards dealt since the last shuffle, in the order they were dealt
+    seen: Vec<Card>,
     /// The running count
     counter: HiLoCounter,
     /// the number of decks
     decks: usize,
+    /// The source of randomness for shuffling
+    rng: ChaCha12Rng,
+    /// Callbacks subscribed to events
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: Vec<Observer>,
 }
 
 impl Shoe {
     /// Create a new shoe with the given number of decks
     pub fn new(decks: usize) -> Self {
-        let mut cards = Vec::new();
-
-        for &s in &[Suit::Heart, Suit::Diamond, Suit::Club, Suit::Spade] {
-            for &r in &[
-                Rank::Ace,
-                Rank::Two,
-                Rank::Three,
-                Rank::Four,
-                Rank::Five,
-                Rank::Six,
-                Rank::Seven,
-                Rank::Eight,
-                Rank::Nine,
-                Rank::Ten,
-                Rank::Jack,
-                Rank::Queen,
-                Rank::King,
-            ] {
+        ShoeBuilder::new().decks(decks).build()
+    }
+}
+
+/// A builder for shoes with a custom composition of cards
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShoeBuilder {
+    /// The cards to load into the shoe
+    cards: Vec<Card>,
+    /// The seed for shuffling
+    seed: Option<u64>,
+    /// Whether to deal the cards in the order they were added instead of shuffling
+    stacked: bool,
+}
+
+impl ShoeBuilder {
+    /// Create a builder for an empty shoe
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `decks` standard 52-card decks
+    pub fn decks(self, decks: usize) -> Self {
+        self.partial_decks(decks, &Suit::ALL)
+    }
+
+    /// Add `decks` Spanish 48-card decks (standard decks with the 10s removed)
+    pub fn spanish_decks(mut self, decks: usize) -> Self {
+        let spanish = ShoeBuilder::new().decks(decks).remove_rank(Rank::Ten);
+        self.cards.extend(spanish.cards);
+        self
+    }
+
+    /// Add `decks` decks containing only the cards of the given `suits`
+    pub fn partial_decks(mut self, decks: usize, suits: &[Suit]) -> Self {
+        for &s in suits {
+            for r in Rank::iter() {
                 for _ in 0..decks {
-                    cards.push(Card::new(s, r));
+                    self.cards.push(Card::new(s, r));
                 }
             }
         }
+        self
+    }
+
+    /// Add `count` copies of `card`
+    pub fn add_card(mut self, card: Card, count: usize) -> Self {
+        self.cards.extend(std::iter::repeat_n(card, count));
+        self
+    }
+
+    /// Add `count` extra cards of rank `rank`, cycling through the suits
+    pub fn add_rank(mut self, rank: Rank, count: usize) -> Self {
+        self.cards
+            .extend((0..count).map(|i| Card::new(Suit::ALL[i % Suit::ALL.len()], rank)));
+        self
+    }
+
+    /// Add an arbitrary multiset of cards
+    pub fn cards(mut self, cards: impl IntoIterator<Item = Card>) -> Self {
+        self.cards.extend(cards);
+        self
+    }
+
+    /// Remove up to `count` copies of `card`
+    pub fn remove_card(mut self, card: Card, count: usize) -> Self {
+        let mut removed = 0;
+        self.cards.retain(|&c| {
+            if c == card && removed < count {
+                removed += 1;
+                false
+            } else {
+                true
+            }
+        });
+        self
+    }
+
+    /// Remove all cards of rank `rank`
+    pub fn remove_rank(mut self, rank: Rank) -> Self {
+        self.cards.retain(|c| c.rank() != rank);
+        self
+    }
+
+    /// Seed the shoe's shuffles so that they are reproducible
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
 
-        let mut shoe = Self {
-            cards,
+    /// Deal the cards in the order they were added instead of shuffling when built.
+    /// Later shuffles are still random.
+    pub fn stacked(mut self) -> Self {
+        self.stacked = true;
+        self
+    }
+
+    /// Build and shuffle the shoe.
+    /// The shoe counts as one deck per 52 cards, rounded up.
+    pub fn build(self) -> Shoe {
+        let decks = self.cards.len().div_ceil(52).max(1);
+        let mut shoe = Shoe {
+            cards: self.cards.clone(),
+            composition: self.cards,
+            seen: Vec::new(),
             counter: HiLoCounter::new(decks),
             decks,
+            rng: match self.seed {
+                Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+                None => ChaCha12Rng::from_rng(&mut rng()),
+            },
+            observers: Vec::new(),
         };
-        shoe.shuffle();
+        if self.stacked {
+            shoe.cards.reverse();
+        } else {
+            shoe.shuffle();
+        }
         shoe
     }
 }
@@ -125,14 +261,81 @@ impl Shoe {
 
     /// Shuffle the shoe
     fn shuffle(&mut self) {
-        let mut rng = rng();
-        self.cards.shuffle(&mut rng);
+        self.emit(Event::ShuffleStarted);
+        self.cards.shuffle(&mut self.rng);
+    }
+
+    /// Subscribe `observer` to the events of the shoe
+    pub fn subscribe(&mut self, observer: impl FnMut(&Event) + Send + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Notify all observers of `event`
+    pub(crate) fn emit(&mut self, event: Event) {
+        for observer in &mut self.observers {
+            observer(&event);
+        }
+    }
+
+    /// Reshuffle the shoe by physically shuffling the previous shoe with `procedure`.
+    ///
+    /// The pack handed to the procedure is the remaining stub (top card first) placed on
+    /// top of the discard tray (most recently dealt card first). The top of the resulting
+    /// pack is dealt first.
+    pub fn shuffle_with(&mut self, procedure: &impl ShuffleProcedure) {
+        self.emit(Event::ShuffleStarted);
+        let mut pack = self.pack();
+        procedure.apply(&mut pack, &mut self.rng);
+        self.cards = pack.into_iter().rev().collect();
+        self.seen.clear();
+        self.counter.clear();
+    }
+
+    /// Return the last card dealt to the top of the shoe
+    pub(crate) fn undeal(&mut self) -> Option<Card> {
+        let card = self.seen.pop()?;
+        self.counter.remove(card);
+        self.cards.push(card);
+        self.emit(Event::CardReturned(card));
+        Some(card)
+    }
+
+    /// Returns the cards the shoe is loaded with when full
+    pub(crate) fn composition(&self) -> &[Card] {
+        &self.composition
+    }
+
+    /// Returns the stub (top card first) on top of the discard tray (most recently dealt card first)
+    pub(crate) fn pack(&self) -> Vec<Card> {
+        self.cards
+            .iter()
+            .rev()
+            .chain(self.seen.iter().rev())
+            .copied()
+            .collect()
+    }
+
+    /// Returns the cards remaining in the shoe in the order they will be dealt
+    pub fn upcoming(&self) -> impl Iterator<Item = Card> + '_ {
+        self.cards.iter().rev().copied()
     }
 
     /// Deal a card from the shoe
     pub fn deal(&mut self) -> Option<Card> {
+        self.deal_card(true)
+    }
+
+    /// Deal a card face down from the shoe
+    pub fn deal_face_down(&mut self) -> Option<Card> {
+        self.deal_card(false)
+    }
+
+    /// Deal a card from the shoe, face up or down
+    fn deal_card(&mut self, face_up: bool) -> Option<Card> {
         if let Some(card) = self.cards.pop() {
             self.counter.insert(card);
+            self.seen.push(card);
+            self.emit(Event::CardDealt { card, face_up });
             Some(card)
         } else {
             None
@@ -149,13 +352,52 @@ impl Shoe {
         self.counter.count()
     }
 
+    /// Return the hi-lo running count divided by the number of decks remaining in the shoe
+    pub fn true_count(&self) -> f32 {
+        if self.cards.is_empty() {
+            return 0.0;
+        }
+        (self.counter.running() as f32) / (self.cards.len() as f32 / 52.0)
+    }
+
+    /// Returns the number of cards in the shoe when it is full
+    pub fn capacity(&self) -> usize {
+        self.composition.len()
+    }
+
+    /// Returns the number of cards of rank `rank` remaining in the shoe
+    pub fn remaining(&self, rank: Rank) -> usize {
+        self.cards.iter().filter(|c| c.rank() == rank).count()
+    }
+
+    /// Returns the number of cards remaining in the shoe for each rank the shoe is loaded with
+    pub fn remaining_ranks(&self) -> BTreeMap<Rank, usize> {
+        let mut counts: BTreeMap<Rank, usize> =
+            self.composition.iter().map(|c| (c.rank(), 0)).collect();
+        for card in &self.cards {
+            *counts.entry(card.rank()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns the cards dealt since the last shuffle, in the order they were dealt
+    pub fn seen(&self) -> &[Card] {
+        &self.seen
+    }
+
     /// Returns how far the deck has been penetrated
     pub fn penetration(&self) -> f32 {
-        1.0 - (self.cards.len() as f32) / ((self.decks * 52) as f32)
+        if self.composition.is_empty() {
+            return 1.0;
+        }
+        1.0 - (self.cards.len() as f32) / (self.composition.len() as f32)
     }
 
-    /// Forcibly reset the shoe
+    /// Forcibly reset the shoe, reloading and reshuffling its full composition
     pub fn reset(&mut self) {
-        *self = Self::new(self.decks);
+        self.cards = self.composition.clone();
+        self.seen.clear();
+        self.counter.clear();
+        self.shuffle();
     }
 }
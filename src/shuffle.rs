@@ -0,0 +1,195 @@
+/*!
+
+  Physical shuffle procedures
+
+  Unlike the perfect shuffle of a fresh [Shoe](crate::Shoe), these procedures model the
+  imperfect operations a dealer performs on the discards and the remaining stub of the
+  previous shoe. Procedures operate on a pack where index 0 is the top card, and
+  compose with [ShuffleProcedure::then] and [ShuffleProcedure::repeat].
+
+*/
+
+use rand::{Rng, seq::SliceRandom};
+
+/// An operation that rearranges a pack of cards
+pub trait ShuffleProcedure {
+    /// Rearrange `pack` in place. Index 0 is the top of the pack.
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng);
+
+    /// Perform `self` followed by `next`
+    fn then<P: ShuffleProcedure>(self, next: P) -> Then<Self, P>
+    where
+        Self: Sized,
+    {
+        Then { first: self, next }
+    }
+
+    /// Perform `self` `times` times in a row
+    fn repeat(self, times: usize) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        Repeat {
+            procedure: self,
+            times,
+        }
+    }
+}
+
+/// Returns a cut point following a binomial distribution around the middle of `n` cards
+fn binomial_cut(n: usize, rng: &mut impl Rng) -> usize {
+    (0..n).filter(|_| rng.random_bool(0.5)).count()
+}
+
+/// A Gilbert-Shannon-Reeds riffle shuffle.
+/// The pack is cut binomially and the halves are interleaved, dropping a card from each
+/// half with probability proportional to the size of that half.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Riffle;
+
+impl ShuffleProcedure for Riffle {
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng) {
+        let cut = binomial_cut(pack.len(), rng);
+        let mut right = pack.split_off(cut).into_iter();
+        let mut left = std::mem::take(pack).into_iter();
+        let (mut l, mut r) = (left.len(), right.len());
+        while l + r > 0 {
+            if rng.random_range(0..l + r) < l {
+                pack.extend(left.next());
+                l -= 1;
+            } else {
+                pack.extend(right.next());
+                r -= 1;
+            }
+        }
+    }
+}
+
+/// A strip cut. Packets of roughly equal size are pulled off the top one at a time and
+/// stacked, reversing the order of the packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StripCut {
+    /// The number of packets to strip
+    pub strips: usize,
+}
+
+impl ShuffleProcedure for StripCut {
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng) {
+        let base = (pack.len() / self.strips.max(1)).max(1);
+        let mut packets = Vec::new();
+        while !pack.is_empty() {
+            let size = rng
+                .random_range(base - base / 2..=base + base / 2)
+                .clamp(1, pack.len());
+            let rest = pack.split_off(size);
+            packets.push(std::mem::replace(pack, rest));
+        }
+        pack.extend(packets.into_iter().rev().flatten());
+    }
+}
+
+/// A single cut near the middle of the pack, moving the top portion to the bottom
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cut;
+
+impl ShuffleProcedure for Cut {
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng) {
+        let cut = binomial_cut(pack.len(), rng);
+        pack.rotate_left(cut);
+    }
+}
+
+/// Plugging. The pack is cut near the middle, and the top portion is broken into
+/// `packets` clumps which are each inserted at a random position in the bottom portion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plug {
+    /// The number of clumps to plug
+    pub packets: usize,
+}
+
+impl ShuffleProcedure for Plug {
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng) {
+        let cut = binomial_cut(pack.len(), rng);
+        let bottom = pack.split_off(cut);
+        let mut top = std::mem::replace(pack, bottom);
+        let size = top.len().div_ceil(self.packets.max(1)).max(1);
+        while !top.is_empty() {
+            let clump: Vec<T> = top.drain(..size.min(top.len())).collect();
+            let at = rng.random_range(0..=pack.len());
+            pack.splice(at..at, clump);
+        }
+    }
+}
+
+/// A wash, which randomizes the pack completely
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wash;
+
+impl ShuffleProcedure for Wash {
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng) {
+        pack.shuffle(rng);
+    }
+}
+
+/// A zone shuffle. The pack is split in half and each half into `zones` grabs.
+/// The i-th grab of each half are combined and shuffled with `procedure`,
+/// then the results are stacked in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneShuffle<P> {
+    /// The number of grabs taken from each half
+    pub zones: usize,
+    /// The procedure applied to each pair of grabs
+    pub procedure: P,
+}
+
+impl<P: ShuffleProcedure> ShuffleProcedure for ZoneShuffle<P> {
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng) {
+        let zones = self.zones.max(1);
+        let mut second = pack.split_off(pack.len() / 2);
+        let mut first = std::mem::take(pack);
+        let (a, b) = (first.len().div_ceil(zones), second.len().div_ceil(zones));
+        for _ in 0..zones {
+            let mut grab: Vec<T> = first.drain(..a.min(first.len())).collect();
+            grab.extend(second.drain(..b.min(second.len())));
+            self.procedure.apply(&mut grab, rng);
+            pack.extend(grab);
+        }
+    }
+}
+
+/// Two procedures performed in sequence. See [ShuffleProcedure::then].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Then<A, B> {
+    first: A,
+    next: B,
+}
+
+impl<A: ShuffleProcedure, B: ShuffleProcedure> ShuffleProcedure for Then<A, B> {
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng) {
+        self.first.apply(pack, rng);
+        self.next.apply(pack, rng);
+    }
+}
+
+/// A procedure performed several times. See [ShuffleProcedure::repeat].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Repeat<P> {
+    procedure: P,
+    times: usize,
+}
+
+impl<P: ShuffleProcedure> ShuffleProcedure for Repeat<P> {
+    fn apply<T>(&self, pack: &mut Vec<T>, rng: &mut impl Rng) {
+        for _ in 0..self.times {
+            self.procedure.apply(pack, rng);
+        }
+    }
+}
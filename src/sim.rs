@@ -0,0 +1,672 @@
+/*!
+
+  Monte Carlo simulation
+
+  A [Simulator] plays one seat by a [PlayingStrategy] and a [BettingStrategy] under a set
+  of [TableRules], and collects the results in [SimStats]. Rounds are dealt directly from a
+  [Shoe] rather than through a [Table](crate::table::Table), which records history and
+  notifies observers, so that millions of rounds can be played quickly, and which only
+  hits and stands. The dealer draws by [TableRules::dealer_hits] and hands are settled by
+  [Outcome::of] and [TableRules::payout], as they are at a table.
+
+  The player keeps the count with a [Counter], [HiLoCounter] by default, whose true count
+  sizes the bets and decides when a back-counting player sits down.
+
+  A back-counting player set up with [Simulator::wonging] watches rounds without wagering
+  until the true count reaches an entry threshold, and leaves when it falls below an exit
+  threshold. Watched rounds are counted separately as [observed](SimStats::observed).
+
+  [Simulator::play_rounds_sharded] splits the rounds into shards of [SHARD_ROUNDS] rounds,
+  each dealt from a fresh shoe seeded from a master seed. With the `rayon` feature, the
+  shards are played on multiple threads. The statistics are the same however many threads
+  play the shards.
+
+*/
+
+use crate::{
+    betting::{BettingStrategy, Flat},
+    card::Card,
+    hand::Hand,
+    shoe::{Counter, HiLoCounter, Shoe, ShoeBuilder},
+    strategy::{BasicStrategy, PlayingStrategy},
+    table::{Action, Outcome, TableRules},
+};
+use std::marker::PhantomData;
+
+/// Statistics of simulated rounds
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimStats {
+    /// The number of rounds played
+    rounds: u64,
+    /// The number of rounds watched without wagering
+    observed: u64,
+    /// The number of hands played, counting each hand of a split
+    hands: u64,
+    /// The units wagered on the initial hands
+    wagered: f64,
+    /// The units won, negative for a loss
+    net: f64,
+    /// The running mean of the units won per round
+    mean: f64,
+    /// The running sum of squared differences from the mean
+    m2: f64,
+    /// The number of rounds won
+    wins: u64,
+    /// The number of rounds lost
+    losses: u64,
+    /// The number of rounds pushed
+    pushes: u64,
+    /// The number of rounds won with a blackjack
+    blackjacks: u64,
+}
+
+impl SimStats {
+    /// Create empty statistics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a round where `wagered` units were bet on the initial hand, `hands` hands were
+    /// played and `net` units were won
+    pub fn record(&mut self, wagered: f64, hands: u64, net: f64, blackjack: bool) {
+        self.rounds += 1;
+        self.hands += hands;
+        self.wagered += wagered;
+        self.net += net;
+
+        let delta = net - self.mean;
+        self.mean += delta / self.rounds as f64;
+        self.m2 += delta * (net - self.mean);
+
+        if blackjack {
+            self.blackjacks += 1;
+        } else if net > 0.0 {
+            self.wins += 1;
+        } else if net < 0.0 {
+            self.losses += 1;
+        } else {
+            self.pushes += 1;
+        }
+    }
+
+    /// Add a round watched without wagering
+    pub fn observe(&mut self) {
+        self.observed += 1;
+    }
+
+    /// Merge the statistics of `other` into these, as if its rounds were played after these
+    pub fn merge(&mut self, other: &SimStats) {
+        if other.rounds == 0 {
+            self.observed += other.observed;
+            return;
+        }
+        let rounds = self.rounds + other.rounds;
+        let delta = other.mean - self.mean;
+        let weight = self.rounds as f64 * other.rounds as f64 / rounds as f64;
+        self.mean += delta * other.rounds as f64 / rounds as f64;
+        self.m2 += other.m2 + delta * delta * weight;
+        self.rounds = rounds;
+        self.observed += other.observed;
+        self.hands += other.hands;
+        self.wagered += other.wagered;
+        self.net += other.net;
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.pushes += other.pushes;
+        self.blackjacks += other.blackjacks;
+    }
+
+    /// Returns the number of rounds played
+    pub fn rounds(&self) -> u64 {
+        self.rounds
+    }
+
+    /// Returns the number of rounds watched without wagering
+    pub fn observed(&self) -> u64 {
+        self.observed
+    }
+
+    /// Returns the fraction of the rounds dealt that were played
+    pub fn play_rate(&self) -> f64 {
+        let dealt = self.rounds + self.observed;
+        if dealt == 0 {
+            0.0
+        } else {
+            self.rounds as f64 / dealt as f64
+        }
+    }
+
+    /// Returns the number of hands played, counting each hand of a split
+    pub fn hands(&self) -> u64 {
+        self.hands
+    }
+
+    /// Returns the units wagered on the initial hands
+    pub fn wagered(&self) -> f64 {
+        self.wagered
+    }
+
+    /// Returns the units won, negative for a loss
+    pub fn net(&self) -> f64 {
+        self.net
+    }
+
+    /// Returns the expected units won per round
+    pub fn ev(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the expected units won per unit wagered on the initial hand
+    pub fn ev_per_unit(&self) -> f64 {
+        if self.wagered == 0.0 {
+            0.0
+        } else {
+            self.net / self.wagered
+        }
+    }
+
+    /// Returns the sample variance of the units won per round
+    pub fn variance(&self) -> f64 {
+        if self.rounds < 2 {
+            0.0
+        } else {
+            self.m2 / (self.rounds - 1) as f64
+        }
+    }
+
+    /// Returns the sample standard deviation of the units won per round
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Returns the standard error of [ev](Self::ev)
+    pub fn std_error(&self) -> f64 {
+        if self.rounds == 0 {
+            0.0
+        } else {
+            self.std_dev() / (self.rounds as f64).sqrt()
+        }
+    }
+
+    /// Returns the confidence interval of [ev](Self::ev) within `z` standard errors,
+    /// such as 1.96 for 95% confidence
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let margin = z * self.std_error();
+        (self.mean - margin, self.mean + margin)
+    }
+
+    /// Returns the number of `count` rounds per round played
+    fn frequency(&self, count: u64) -> f64 {
+        if self.rounds == 0 {
+            0.0
+        } else {
+            count as f64 / self.rounds as f64
+        }
+    }
+
+    /// Returns the fraction of rounds won, other than with a blackjack
+    pub fn win_rate(&self) -> f64 {
+        self.frequency(self.wins)
+    }
+
+    /// Returns the fraction of rounds lost
+    pub fn loss_rate(&self) -> f64 {
+        self.frequency(self.losses)
+    }
+
+    /// Returns the fraction of rounds pushed
+    pub fn push_rate(&self) -> f64 {
+        self.frequency(self.pushes)
+    }
+
+    /// Returns the fraction of rounds won with a blackjack
+    pub fn blackjack_rate(&self) -> f64 {
+        self.frequency(self.blackjacks)
+    }
+}
+
+/// The number of rounds in each shard of [Simulator::play_rounds_sharded]
+pub const SHARD_ROUNDS: u64 = 100_000;
+
+/// Derive the seed of shard number `shard` from the master `seed` (SplitMix64)
+fn shard_seed(seed: u64, shard: u64) -> u64 {
+    let mut z = seed.wrapping_add((shard + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Returns the running count by `C` of the cards dealt from `shoe` since its last shuffle
+fn running<C: Counter>(shoe: &Shoe) -> f32 {
+    shoe.seen().iter().map(|card| C::tag(card.rank())).sum()
+}
+
+/// A hand played by the simulated player
+struct PlayerHand {
+    /// The cards of the hand
+    hand: Hand,
+    /// The units wagered on the hand
+    wager: f64,
+    /// Whether the hand came from a split
+    split: bool,
+    /// Whether the hand was surrendered
+    surrendered: bool,
+}
+
+impl PlayerHand {
+    /// A hand of `cards` with `wager` units
+    fn new(cards: &[Card], wager: f64, split: bool) -> Self {
+        let mut hand = Hand::default();
+        for &card in cards {
+            hand.insert(card);
+        }
+        Self {
+            hand,
+            wager,
+            split,
+            surrendered: false,
+        }
+    }
+
+    /// Returns true if the hand is a pair of split aces
+    fn split_aces(&self) -> bool {
+        self.split && self.hand.cards()[0].is_ace()
+    }
+}
+
+/// Plays rounds of blackjack and collects statistics
+pub struct Simulator<P = BasicStrategy, B = Flat, C = HiLoCounter> {
+    /// The rules of the table
+    rules: TableRules,
+    /// The shoe dealt from
+    shoe: Shoe,
+    /// The strategy playing the hands
+    strategy: P,
+    /// The strategy sizing the bets
+    betting: B,
+    /// The player's bankroll in units
+    bankroll: f64,
+    /// The true counts at which a back-counting player enters and leaves the table
+    wonging: Option<(f32, f32)>,
+    /// Whether the player is seated at the table
+    seated: bool,
+    /// The running count of the cards dealt since the last shuffle, by the player's counter
+    running: f32,
+    /// The number of shoes dealt out in the middle of a round
+    exhausted: u64,
+    /// The counting system the player keeps the count with
+    counter: PhantomData<fn() -> C>,
+}
+
+impl Simulator<BasicStrategy, Flat> {
+    /// Create a simulator under `rules`, playing [BasicStrategy] with a flat bet of 1 unit
+    /// from a bankroll of 1000 units, counting with [HiLoCounter]
+    pub fn new(rules: TableRules) -> Self {
+        Self {
+            rules,
+            shoe: Shoe::new(rules.decks),
+            strategy: BasicStrategy::default(),
+            betting: Flat::default(),
+            bankroll: 1000.0,
+            wonging: None,
+            seated: true,
+            running: 0.0,
+            exhausted: 0,
+            counter: PhantomData,
+        }
+    }
+}
+
+impl<P: PlayingStrategy, B: BettingStrategy, C: Counter> Simulator<P, B, C> {
+    /// Play hands with `strategy` instead
+    pub fn with_strategy<T: PlayingStrategy>(self, strategy: T) -> Simulator<T, B, C> {
+        Simulator {
+            rules: self.rules,
+            shoe: self.shoe,
+            strategy,
+            betting: self.betting,
+            bankroll: self.bankroll,
+            wonging: self.wonging,
+            seated: self.seated,
+            running: self.running,
+            exhausted: self.exhausted,
+            counter: PhantomData,
+        }
+    }
+
+    /// Size bets with `betting` instead
+    pub fn with_betting<T: BettingStrategy>(self, betting: T) -> Simulator<P, T, C> {
+        Simulator {
+            rules: self.rules,
+            shoe: self.shoe,
+            strategy: self.strategy,
+            betting,
+            bankroll: self.bankroll,
+            wonging: self.wonging,
+            seated: self.seated,
+            running: self.running,
+            exhausted: self.exhausted,
+            counter: PhantomData,
+        }
+    }
+
+    /// Keep the count with the counting system `T` instead
+    pub fn with_counter<T: Counter>(self) -> Simulator<P, B, T> {
+        Simulator {
+            rules: self.rules,
+            running: running::<T>(&self.shoe),
+            exhausted: self.exhausted,
+            shoe: self.shoe,
+            strategy: self.strategy,
+            betting: self.betting,
+            bankroll: self.bankroll,
+            wonging: self.wonging,
+            seated: self.seated,
+            counter: PhantomData,
+        }
+    }
+
+    /// Deal from `shoe` instead, such as a shoe with a custom composition
+    pub fn with_shoe(mut self, shoe: Shoe) -> Self {
+        self.running = running::<C>(&shoe);
+        self.shoe = shoe;
+        self
+    }
+
+    /// Seed the shuffles of a fresh shoe, loaded with the cards of the current one, so that the
+    /// simulation is reproducible
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.shoe = ShoeBuilder::new()
+            .cards(self.shoe.composition().iter().copied())
+            .seed(seed)
+            .build();
+        self.running = 0.0;
+        self
+    }
+
+    /// Start from a bankroll of `units`
+    pub fn bankroll(mut self, units: f64) -> Self {
+        self.bankroll = units;
+        self
+    }
+
+    /// Back-count the table: watch rounds without wagering, enter when the true count reaches
+    /// `entry` and leave when it falls below `exit`
+    pub fn wonging(mut self, entry: f32, exit: f32) -> Self {
+        self.wonging = Some((entry, exit));
+        self.seated = false;
+        self
+    }
+
+    /// Returns the rules of the table
+    pub fn rules(&self) -> &TableRules {
+        &self.rules
+    }
+
+    /// Returns the shoe dealt from
+    pub fn shoe(&self) -> &Shoe {
+        &self.shoe
+    }
+
+    /// Returns the player's current bankroll in units
+    pub fn current_bankroll(&self) -> f64 {
+        self.bankroll
+    }
+
+    /// Returns the player's running count divided by the number of decks remaining in the shoe
+    pub fn true_count(&self) -> f32 {
+        if self.shoe.is_empty() {
+            return 0.0;
+        }
+        self.running / (self.shoe.len() as f32 / 52.0)
+    }
+
+    /// Deal `rounds` rounds. Rounds the player watches without wagering are counted as observed.
+    pub fn play_rounds(&mut self, rounds: u64) -> SimStats {
+        let mut stats = SimStats::new();
+        for _ in 0..rounds {
+            self.play_round(&mut stats);
+        }
+        stats
+    }
+
+    /// Deal rounds until `shoes` shoes have been dealt down to the penetration of the rules.
+    /// A shoe dealt out in the middle of a round is finished too.
+    pub fn play_shoes(&mut self, shoes: u64) -> SimStats {
+        let mut stats = SimStats::new();
+        let mut finished = 0;
+        while finished < shoes {
+            if self.needs_shuffle() {
+                finished += 1;
+                if finished == shoes {
+                    break;
+                }
+            }
+            let exhausted = self.exhausted;
+            self.play_round(&mut stats);
+            finished += self.exhausted - exhausted;
+        }
+        stats
+    }
+
+    /// Play `rounds` rounds in shards of [SHARD_ROUNDS] rounds, each dealt from a fresh copy
+    /// of the shoe seeded from `seed` and starting from the current bankroll. With the `rayon`
+    /// feature, the shards are played in parallel. The statistics only depend on `seed`, not on
+    /// the number of threads. The simulator's own shoe and bankroll are left untouched.
+    ///
+    /// Since every shard restarts from the current bankroll, a [BettingStrategy] sizing bets by
+    /// the bankroll, such as [Kelly](crate::betting::Kelly), is played per shard rather than
+    /// from one bankroll carried through every round.
+    pub fn play_rounds_sharded(&self, rounds: u64, seed: u64) -> SimStats
+    where
+        P: Clone + Send + Sync,
+        B: Clone + Send + Sync,
+    {
+        let shards = rounds.div_ceil(SHARD_ROUNDS);
+        let (rules, bankroll, wonging) = (self.rules, self.bankroll, self.wonging);
+        let (strategy, betting) = (&self.strategy, &self.betting);
+        let composition = self.shoe.composition();
+        let play_shard = |shard: u64| {
+            let shoe = ShoeBuilder::new()
+                .cards(composition.iter().copied())
+                .seed(shard_seed(seed, shard))
+                .build();
+            let mut sim: Simulator<P, B, C> = Simulator {
+                rules,
+                shoe,
+                strategy: strategy.clone(),
+                betting: betting.clone(),
+                bankroll,
+                wonging,
+                seated: wonging.is_none(),
+                running: 0.0,
+                exhausted: 0,
+                counter: PhantomData,
+            };
+            sim.play_rounds(SHARD_ROUNDS.min(rounds - shard * SHARD_ROUNDS))
+        };
+
+        #[cfg(feature = "rayon")]
+        let results: Vec<SimStats> = {
+            use rayon::prelude::*;
+            (0..shards).into_par_iter().map(play_shard).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let results: Vec<SimStats> = (0..shards).map(play_shard).collect();
+
+        let mut stats = SimStats::new();
+        for result in &results {
+            stats.merge(result);
+        }
+        stats
+    }
+
+    /// Returns true if the shoe is past the penetration of the rules
+    fn needs_shuffle(&self) -> bool {
+        self.shoe.penetration() > self.rules.penetration
+    }
+
+    /// Reload and reshuffle the shoe, clearing the count
+    fn reset(&mut self) {
+        self.shoe.reset();
+        self.running = 0.0;
+    }
+
+    /// Deal a card, reshuffling if the shoe runs out mid-round
+    fn draw(&mut self) -> Card {
+        let card = match self.shoe.deal() {
+            Some(card) => card,
+            None => {
+                self.reset();
+                self.exhausted += 1;
+                self.shoe.deal().expect("a reshuffled shoe has cards")
+            }
+        };
+        self.running += C::tag(card.rank());
+        card
+    }
+
+    /// Play a round, adding it to `stats`
+    fn play_round(&mut self, stats: &mut SimStats) {
+        if self.needs_shuffle() {
+            self.reset();
+        }
+        let true_count = self.true_count();
+        if let Some((entry, exit)) = self.wonging {
+            if self.seated && true_count < exit {
+                self.seated = false;
+            } else if !self.seated && true_count >= entry {
+                self.seated = true;
+            }
+        }
+        let wager = if self.seated {
+            self.betting.bet(true_count, self.bankroll).units() as f64
+        } else {
+            0.0
+        };
+
+        let cards = [self.draw(), self.draw(), self.draw(), self.draw()];
+        let upcard = cards[1];
+        let mut dealer = Hand::default();
+        dealer.insert(cards[1]);
+        dealer.insert(cards[3]);
+        let player = PlayerHand::new(&[cards[0], cards[2]], wager, false);
+
+        let (hands, net, blackjack) =
+            if player.hand.blackjack() || (self.rules.peek && dealer.blackjack()) {
+                let outcome = Outcome::of(&player.hand, true, &dealer);
+                (
+                    1,
+                    self.rules.payout(outcome, wager),
+                    outcome == Outcome::Blackjack,
+                )
+            } else {
+                let hands = self.play_hands(player, upcard);
+                if hands.iter().any(|h| !h.surrendered && !h.hand.busted()) {
+                    self.play_dealer(&mut dealer);
+                }
+                let net = hands.iter().map(|h| self.settle(h, &dealer)).sum();
+                (hands.len() as u64, net, false)
+            };
+
+        if wager == 0.0 {
+            stats.observe();
+        } else {
+            self.bankroll += net;
+            stats.record(wager, hands, net, blackjack);
+        }
+    }
+
+    /// Play `player` and any hands split from it against `upcard`, returning the finished hands.
+    /// An action that is not allowed stands.
+    fn play_hands(&mut self, player: PlayerHand, upcard: Card) -> Vec<PlayerHand> {
+        let mut finished = Vec::new();
+        let mut pending = vec![player];
+        let mut splits = 0;
+        while let Some(mut current) = pending.pop() {
+            if current.hand.len() == 1 {
+                let card = self.draw();
+                current.hand.insert(card);
+            }
+            loop {
+                if current.hand.busted() || current.hand.value() == Some(21) {
+                    break;
+                }
+                let allowed = self.allowed(&current, splits, finished.is_empty() && splits == 0);
+                let action = self.strategy.action(&current.hand, upcard, &allowed);
+                match action {
+                    Action::Hit if allowed.contains(&Action::Hit) => {
+                        let card = self.draw();
+                        current.hand.insert(card);
+                    }
+                    Action::Double if allowed.contains(&Action::Double) => {
+                        current.wager *= 2.0;
+                        let card = self.draw();
+                        current.hand.insert(card);
+                        break;
+                    }
+                    Action::Split if allowed.contains(&Action::Split) => {
+                        splits += 1;
+                        let [first, second] = [current.hand.cards()[0], current.hand.cards()[1]];
+                        pending.push(PlayerHand::new(&[second], current.wager, true));
+                        current = PlayerHand::new(&[first], current.wager, true);
+                        let card = self.draw();
+                        current.hand.insert(card);
+                    }
+                    Action::Surrender if allowed.contains(&Action::Surrender) => {
+                        current.surrendered = true;
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+            finished.push(current);
+        }
+        finished
+    }
+
+    /// Returns the actions allowed on `current` after `splits` splits, where `first` is
+    /// true for the first decision of the round
+    fn allowed(&self, current: &PlayerHand, splits: usize, first: bool) -> Vec<Action> {
+        let split_aces = current.split_aces();
+        let mut allowed = vec![Action::Stand];
+        if !split_aces || self.rules.hit_split_aces {
+            allowed.push(Action::Hit);
+        }
+        if current.hand.len() == 2 {
+            if (!current.split || self.rules.double_after_split) && !split_aces {
+                allowed.push(Action::Double);
+            }
+            let [a, b] = [current.hand.cards()[0], current.hand.cards()[1]];
+            if a.values() == b.values()
+                && splits < self.rules.max_splits
+                && (!split_aces || self.rules.resplit_aces)
+            {
+                allowed.push(Action::Split);
+            }
+            if first && self.rules.surrender {
+                allowed.push(Action::Surrender);
+            }
+        }
+        allowed
+    }
+
+    /// Draw cards to the dealer until they stand
+    fn play_dealer(&mut self, dealer: &mut Hand) {
+        while self.rules.dealer_hits(dealer) {
+            let card = self.draw();
+            dealer.insert(card);
+        }
+    }
+
+    /// Returns the units won on `hand` against the dealer's final hand
+    fn settle(&self, hand: &PlayerHand, dealer: &Hand) -> f64 {
+        // Without a peek, a dealer blackjack takes a surrendered hand's whole wager
+        if hand.surrendered && !dealer.blackjack() {
+            return -hand.wager / 2.0;
+        }
+        let outcome = Outcome::of(&hand.hand, false, dealer);
+        self.rules.payout(outcome, hand.wager)
+    }
+}
@@ -0,0 +1,415 @@
+/*!
+
+  Playing strategies
+
+*/
+
+use crate::{
+    card::{Card, Rank},
+    hand::{Hand, HandClass, ParseHandClassError},
+    table::Action,
+};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+/// A strategy for playing a hand
+pub trait PlayingStrategy {
+    /// Choose an action for `hand` against the dealer's `upcard`, among the actions `allowed`
+    fn action(&self, hand: &Hand, upcard: Card, allowed: &[Action]) -> Action;
+}
+
+/// The row of a strategy chart a hand is played from
+pub type ChartRow = HandClass;
+
+/// An entry of a strategy chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChartEntry {
+    /// Hit
+    Hit,
+    /// Stand
+    Stand,
+    /// Double if allowed, otherwise hit
+    DoubleHit,
+    /// Double if allowed, otherwise stand
+    DoubleStand,
+    /// Split
+    Split,
+    /// Surrender if allowed, otherwise hit
+    SurrenderHit,
+    /// Surrender if allowed, otherwise stand
+    SurrenderStand,
+    /// Surrender if allowed, otherwise split
+    SurrenderSplit,
+}
+
+impl ChartEntry {
+    /// The actions to try in order of preference
+    pub(crate) fn preferences(self) -> &'static [Action] {
+        match self {
+            Self::Hit => &[Action::Hit],
+            Self::Stand => &[Action::Stand],
+            Self::DoubleHit => &[Action::Double, Action::Hit],
+            Self::DoubleStand => &[Action::Double, Action::Stand],
+            Self::Split => &[Action::Split],
+            Self::SurrenderHit => &[Action::Surrender, Action::Hit],
+            Self::SurrenderStand => &[Action::Surrender, Action::Stand],
+            Self::SurrenderSplit => &[Action::Surrender, Action::Split],
+        }
+    }
+}
+
+impl Display for ChartEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hit => write!(f, "H"),
+            Self::Stand => write!(f, "S"),
+            Self::DoubleHit => write!(f, "D"),
+            Self::DoubleStand => write!(f, "Ds"),
+            Self::Split => write!(f, "P"),
+            Self::SurrenderHit => write!(f, "Rh"),
+            Self::SurrenderStand => write!(f, "Rs"),
+            Self::SurrenderSplit => write!(f, "Rp"),
+        }
+    }
+}
+
+impl FromStr for ChartEntry {
+    type Err = ParseChartError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "H" => Ok(Self::Hit),
+            "S" => Ok(Self::Stand),
+            "D" => Ok(Self::DoubleHit),
+            "Ds" => Ok(Self::DoubleStand),
+            "P" => Ok(Self::Split),
+            "Rh" => Ok(Self::SurrenderHit),
+            "Rs" => Ok(Self::SurrenderStand),
+            "Rp" => Ok(Self::SurrenderSplit),
+            _ => Err(ParseChartError(format!("invalid chart entry `{s}`"))),
+        }
+    }
+}
+
+/// An error parsing a strategy chart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseChartError(String);
+
+impl Display for ParseChartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseChartError {}
+
+impl From<ParseHandClassError> for ParseChartError {
+    fn from(err: ParseHandClassError) -> Self {
+        Self(err.to_string())
+    }
+}
+
+/// Returns the column of a chart for the dealer's `upcard`: 2 through 10, then ace
+pub(crate) fn column(upcard: Rank) -> usize {
+    match upcard {
+        Rank::Ace => 9,
+        rank => rank.value() as usize - 2,
+    }
+}
+
+/// A strategy chart, mapping each [ChartRow] and dealer upcard to a [ChartEntry].
+///
+/// Charts are written and parsed one row per line, with one entry per dealer upcard
+/// from 2 through 10 followed by the ace. Blank lines and lines starting with `#` are ignored.
+/// Rows for two cards, such as `10-6`, play that two-card hand instead of its total's row,
+/// for composition-dependent strategy.
+///
+/// ```text
+/// # Dealer: 2  3  4  5  6  7  8  9  10 A
+/// Hard16:   S  S  S  S  S  H  H  Rh Rh Rh
+/// Soft18:   S  Ds Ds Ds Ds S  S  H  H  H
+/// Pair8:    P  P  P  P  P  P  P  P  P  P
+/// 10-2:     H  H  H  S  S  H  H  H  H  H
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chart {
+    /// The entries of each row
+    rows: BTreeMap<ChartRow, [ChartEntry; 10]>,
+    /// The entries of each two-card row, by the cards in the order of [cards_key]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "BTreeMap::is_empty",
+            with = "card_rows"
+        )
+    )]
+    cards: BTreeMap<[Rank; 2], [ChartEntry; 10]>,
+}
+
+/// Returns two cards of `cards`' values, ten-valued cards as [Rank::Ten], the highest first
+/// counting aces high
+fn cards_key(cards: [Rank; 2]) -> [Rank; 2] {
+    let [a, b] = cards.map(|rank| if rank.is_ten_value() { Rank::Ten } else { rank });
+    let high = |rank: Rank| if rank == Rank::Ace { 11 } else { rank.value() };
+    if high(a) >= high(b) { [a, b] } else { [b, a] }
+}
+
+/// Returns the label of a two-card row, like `10-6`
+fn cards_label([a, b]: [Rank; 2]) -> String {
+    format!("{a}-{b}")
+}
+
+/// Parses the label of a two-card row, like `10-6`
+fn parse_cards(label: &str) -> Result<[Rank; 2], ParseChartError> {
+    let err = || ParseChartError(format!("invalid two-card row `{label}`"));
+    let (a, b) = label.split_once('-').ok_or_else(err)?;
+    Ok(cards_key([
+        a.parse().map_err(|_| err())?,
+        b.parse().map_err(|_| err())?,
+    ]))
+}
+
+/// Two-card rows serialize keyed by their labels (`"10-6"`), so that they can key maps
+#[cfg(feature = "serde")]
+mod card_rows {
+    use super::{ChartEntry, ParseChartError, cards_label, parse_cards};
+    use crate::card::Rank;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub(super) fn serialize<S: Serializer>(
+        cards: &BTreeMap<[Rank; 2], [ChartEntry; 10]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            cards
+                .iter()
+                .map(|(&key, entries)| (cards_label(key), entries)),
+        )
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<[Rank; 2], [ChartEntry; 10]>, D::Error> {
+        BTreeMap::<String, [ChartEntry; 10]>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(label, entries)| Ok((parse_cards(&label)?, entries)))
+            .collect::<Result<_, ParseChartError>>()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A basic strategy chart for 4-8 decks, dealer stands on soft 17, double after split and late surrender
+const DEFAULT_CHART: &str = "
+# Dealer: 2  3  4  5  6  7  8  9  10 A
+Hard4:    H  H  H  H  H  H  H  H  H  H
+Hard5:    H  H  H  H  H  H  H  H  H  H
+Hard6:    H  H  H  H  H  H  H  H  H  H
+Hard7:    H  H  H  H  H  H  H  H  H  H
+Hard8:    H  H  H  H  H  H  H  H  H  H
+Hard9:    H  D  D  D  D  H  H  H  H  H
+Hard10:   D  D  D  D  D  D  D  D  H  H
+Hard11:   D  D  D  D  D  D  D  D  D  H
+Hard12:   H  H  S  S  S  H  H  H  H  H
+Hard13:   S  S  S  S  S  H  H  H  H  H
+Hard14:   S  S  S  S  S  H  H  H  H  H
+Hard15:   S  S  S  S  S  H  H  H  Rh H
+Hard16:   S  S  S  S  S  H  H  Rh Rh Rh
+Hard17:   S  S  S  S  S  S  S  S  S  S
+Hard18:   S  S  S  S  S  S  S  S  S  S
+Hard19:   S  S  S  S  S  S  S  S  S  S
+Hard20:   S  S  S  S  S  S  S  S  S  S
+Hard21:   S  S  S  S  S  S  S  S  S  S
+Soft12:   H  H  H  H  H  H  H  H  H  H
+Soft13:   H  H  H  D  D  H  H  H  H  H
+Soft14:   H  H  H  D  D  H  H  H  H  H
+Soft15:   H  H  D  D  D  H  H  H  H  H
+Soft16:   H  H  D  D  D  H  H  H  H  H
+Soft17:   H  D  D  D  D  H  H  H  H  H
+Soft18:   S  Ds Ds Ds Ds S  S  H  H  H
+Soft19:   S  S  S  S  S  S  S  S  S  S
+Soft20:   S  S  S  S  S  S  S  S  S  S
+Soft21:   S  S  S  S  S  S  S  S  S  S
+Pair2:    P  P  P  P  P  P  H  H  H  H
+Pair3:    P  P  P  P  P  P  H  H  H  H
+Pair4:    H  H  H  P  P  H  H  H  H  H
+Pair5:    D  D  D  D  D  D  D  D  H  H
+Pair6:    P  P  P  P  P  H  H  H  H  H
+Pair7:    P  P  P  P  P  P  H  H  H  H
+Pair8:    P  P  P  P  P  P  P  P  P  P
+Pair9:    P  P  P  P  P  S  P  P  S  S
+Pair10:   S  S  S  S  S  S  S  S  S  S
+PairA:    P  P  P  P  P  P  P  P  P  P
+";
+
+impl Chart {
+    /// Returns the entry for `row` against the dealer's `upcard`, if the chart has the row
+    pub fn get(&self, row: ChartRow, upcard: Card) -> Option<ChartEntry> {
+        self.rows
+            .get(&row)
+            .map(|entries| entries[column(upcard.rank())])
+    }
+
+    /// Set the entries of `row`, one per dealer upcard from 2 through 10 followed by the ace
+    pub fn set(&mut self, row: ChartRow, entries: [ChartEntry; 10]) {
+        self.rows.insert(row, entries);
+    }
+
+    /// An iterator over the rows of the chart
+    pub fn rows(&self) -> impl Iterator<Item = (ChartRow, &[ChartEntry; 10])> {
+        self.rows.iter().map(|(&row, entries)| (row, entries))
+    }
+
+    /// Returns the entry for the two-card hand of `cards` against the dealer's `upcard`, if
+    /// the chart has a row for it
+    pub fn get_cards(&self, cards: [Rank; 2], upcard: Card) -> Option<ChartEntry> {
+        self.cards
+            .get(&cards_key(cards))
+            .map(|entries| entries[column(upcard.rank())])
+    }
+
+    /// Set the entries of the two-card row for `cards`, one per dealer upcard from 2 through 10
+    /// followed by the ace
+    pub fn set_cards(&mut self, cards: [Rank; 2], entries: [ChartEntry; 10]) {
+        self.cards.insert(cards_key(cards), entries);
+    }
+
+    /// An iterator over the two-card rows of the chart
+    pub fn card_rows(&self) -> impl Iterator<Item = ([Rank; 2], &[ChartEntry; 10])> {
+        self.cards.iter().map(|(&cards, entries)| (cards, entries))
+    }
+
+    /// Returns the pair row for `hand`, if it is a pair
+    fn pair_row(hand: &Hand) -> Option<ChartRow> {
+        hand.total().pair().map(HandClass::Pair)
+    }
+
+    /// Returns the total row for `hand`
+    fn total_row(hand: &Hand) -> Option<ChartRow> {
+        let total = hand.total();
+        if total.is_bust() {
+            None
+        } else if total.is_soft() {
+            Some(HandClass::Soft(total.total()))
+        } else {
+            Some(HandClass::Hard(total.total()))
+        }
+    }
+}
+
+impl PlayingStrategy for Chart {
+    fn action(&self, hand: &Hand, upcard: Card, allowed: &[Action]) -> Action {
+        let cards = match hand.cards() {
+            [a, b] => self.get_cards([a.rank(), b.rank()], upcard),
+            _ => None,
+        };
+        let entries = [
+            Self::pair_row(hand).and_then(|row| self.get(row, upcard)),
+            cards,
+            Self::total_row(hand).and_then(|row| self.get(row, upcard)),
+        ];
+        for entry in entries.into_iter().flatten() {
+            if let Some(&action) = entry
+                .preferences()
+                .iter()
+                .find(|action| allowed.contains(action))
+            {
+                return action;
+            }
+        }
+        Action::Stand
+    }
+}
+
+impl Display for Chart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Dealer: 2  3  4  5  6  7  8  9  10 A")?;
+        let rows = self
+            .rows
+            .iter()
+            .map(|(row, entries)| (row.to_string(), entries));
+        let cards = self
+            .cards
+            .iter()
+            .map(|(&cards, entries)| (cards_label(cards), entries));
+        for (row, entries) in rows.chain(cards) {
+            write!(f, "{:<9}", format!("{row}:"))?;
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|e| format!("{:<2}", e.to_string()))
+                .collect();
+            writeln!(f, " {}", entries.join(" ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Chart {
+    type Err = ParseChartError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chart = Chart::default();
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (row, entries) = line.split_once(':').ok_or_else(|| {
+                ParseChartError(format!("expected `Row: entries`, found `{line}`"))
+            })?;
+            let entries = entries
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<ChartEntry>, _>>()?;
+            let entries: [ChartEntry; 10] = entries.try_into().map_err(|e: Vec<_>| {
+                ParseChartError(format!(
+                    "expected 10 entries for `{row}`, found {}",
+                    e.len()
+                ))
+            })?;
+            let row = row.trim();
+            if row.contains('-') {
+                chart.set_cards(parse_cards(row)?, entries);
+            } else {
+                chart.set(row.parse()?, entries);
+            }
+        }
+        Ok(chart)
+    }
+}
+
+/// Basic strategy played from a [Chart]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BasicStrategy {
+    /// The chart to play from
+    chart: Chart,
+}
+
+impl Default for BasicStrategy {
+    /// Basic strategy for 4-8 decks, dealer stands on soft 17, double after split and late surrender
+    fn default() -> Self {
+        Self {
+            chart: DEFAULT_CHART.parse().expect("default chart is valid"),
+        }
+    }
+}
+
+impl BasicStrategy {
+    /// Create a basic strategy playing from `chart`
+    pub fn new(chart: Chart) -> Self {
+        Self { chart }
+    }
+
+    /// Returns the chart the strategy plays from
+    pub fn chart(&self) -> &Chart {
+        &self.chart
+    }
+}
+
+impl PlayingStrategy for BasicStrategy {
+    fn action(&self, hand: &Hand, upcard: Card, allowed: &[Action]) -> Action {
+        self.chart.action(hand, upcard, allowed)
+    }
+}
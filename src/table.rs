@@ -4,11 +4,20 @@
 
 */
 
-use crate::{bet::Bet, hand::Hand, shoe::Shoe};
+use crate::{
+    bet::Bet,
+    card::Card,
+    event::Event,
+    hand::Hand,
+    history::{History, RoundRecord, SeatRecord, Step},
+    render::Renderer,
+    shoe::Shoe,
+};
 use std::fmt::Display;
 
 /// Represents the outcome of a hand
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Outcome {
     /// Pays 3:2 or 6:5
     Blackjack,
@@ -20,8 +29,143 @@ pub enum Outcome {
     Push,
 }
 
+impl Outcome {
+    /// Returns the outcome of a player's `hand` against the dealer's finished hand `dealer`.
+    /// A two-card 21 is only a blackjack if the hand is `natural`, which split hands are not.
+    pub fn of(hand: &Hand, natural: bool, dealer: &Hand) -> Self {
+        let blackjack = natural && hand.blackjack();
+        if dealer.blackjack() {
+            return if blackjack {
+                Outcome::Push
+            } else {
+                Outcome::Lose
+            };
+        }
+        if blackjack {
+            return Outcome::Blackjack;
+        }
+        match (hand.value(), dealer.value()) {
+            (None, _) => Outcome::Lose,
+            (Some(_), None) => Outcome::Win,
+            (Some(p), Some(d)) if p > d => Outcome::Win,
+            (Some(p), Some(d)) if p < d => Outcome::Lose,
+            _ => Outcome::Push,
+        }
+    }
+}
+
+/// A decision a player makes on their hand
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    /// Take another card
+    Hit,
+    /// Take no more cards
+    Stand,
+    /// Double the wager and take exactly one more card
+    Double,
+    /// Split a pair into two hands
+    Split,
+    /// Forfeit half the wager and give up the hand
+    Surrender,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hit => write!(f, "Hit"),
+            Self::Stand => write!(f, "Stand"),
+            Self::Double => write!(f, "Double"),
+            Self::Split => write!(f, "Split"),
+            Self::Surrender => write!(f, "Surrender"),
+        }
+    }
+}
+
+/// The rules a table is played by
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableRules {
+    /// The number of decks in the shoe
+    pub decks: usize,
+    /// Whether the dealer hits a soft 17 (H17) instead of standing (S17)
+    pub dealer_hits_soft_17: bool,
+    /// Whether a hand can be doubled after a split
+    pub double_after_split: bool,
+    /// Whether a hand can be surrendered after the dealer checks for blackjack (late surrender)
+    pub surrender: bool,
+    /// The units paid per unit wagered on a blackjack, 1.5 for 3:2 or 1.2 for 6:5
+    pub blackjack_payout: f32,
+    /// The maximum number of times a player can split, 3 for up to 4 hands
+    pub max_splits: usize,
+    /// Whether split aces can be split again
+    pub resplit_aces: bool,
+    /// Whether split aces can take more than one card
+    pub hit_split_aces: bool,
+    /// The penetration (from 0.0-1.0) past which the shoe is reshuffled before the next round
+    pub penetration: f32,
+    /// Whether the dealer checks for blackjack before the players act. Without a peek, a
+    /// dealer blackjack takes doubled and split wagers too.
+    pub peek: bool,
+}
+
+impl TableRules {
+    /// Returns true if the dealer draws another card to `dealer`
+    pub fn dealer_hits(&self, dealer: &Hand) -> bool {
+        match dealer.value() {
+            Some(17) => dealer.is_soft() && self.dealer_hits_soft_17,
+            Some(value) => value < 17,
+            None => false,
+        }
+    }
+
+    /// Returns the units won (positive) or lost (negative) on a `wager` with `outcome`
+    pub fn payout(&self, outcome: Outcome, wager: f64) -> f64 {
+        match outcome {
+            Outcome::Blackjack => wager * self.blackjack_payout as f64,
+            Outcome::Win => wager,
+            Outcome::Lose => -wager,
+            Outcome::Push => 0.0,
+        }
+    }
+}
+
+impl Default for TableRules {
+    /// 6 decks, dealer stands on soft 17, double after split, late surrender, 3:2 blackjack,
+    /// split up to 4 hands, 75% penetration and peek
+    fn default() -> Self {
+        Self {
+            decks: 6,
+            dealer_hits_soft_17: false,
+            double_after_split: true,
+            surrender: true,
+            blackjack_payout: 1.5,
+            max_splits: 3,
+            resplit_aces: false,
+            hit_split_aces: false,
+            penetration: 0.75,
+            peek: true,
+        }
+    }
+}
+
+/// The state of a player's hand before an action, to undo it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Snapshot {
+    /// The seat of the player
+    seat: usize,
+    /// The action taken
+    action: Action,
+    /// The hand before the action
+    hand: Hand,
+    /// The number of cards dealt from the shoe before the action
+    seen: usize,
+}
+
 /// Represents the state of the table
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum TableState {
     /// Open for bets
     Open,
@@ -31,45 +175,115 @@ enum TableState {
     Flipped,
 }
 
-/// A blackjack table
+/// A blackjack table.
+///
+/// With the `serde` feature, a table serializes with its shoe, hands, bets and history,
+/// including mid-round, so a game can be saved and resumed exactly.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Table {
     /// The dealer's hand
     dealer: Hand,
     /// The player hands
     player_hands: Vec<Hand>,
     /// The player bets
-    _player_bets: Vec<Bet>,
+    player_bets: Vec<Bet>,
     /// The shoe
     shoe: Shoe,
-    /// The max deck penetration before reshuffle
-    max_penetration: f32,
-    /// The number of decks to play with
-    num_decks: usize,
+    /// The rules of the table
+    rules: TableRules,
+    /// Whether the cut card has come out of the shoe
+    cut_card_reached: bool,
     /// State of the table
     state: TableState,
+    /// The number of rounds dealt
+    rounds: usize,
+    /// The record of the current round
+    record: RoundRecord,
+    /// The records of past rounds
+    history: History,
+    /// Whether player actions can be undone
+    training: bool,
+    /// Snapshots of the actions that can be undone, most recent last
+    undo: Vec<Snapshot>,
+    /// The actions that can be redone, most recently undone last
+    redo: Vec<(usize, Action)>,
 }
 
 impl Table {
     /// Creates a new blackjack table with `num_decks` decks and `num_spots` bettings spots and `max_penetration` (from 0.0-1.0 before shuffling)
     pub fn new(num_decks: usize, num_spots: usize, max_penetration: f32) -> Self {
-        let shoe = Shoe::new(num_decks);
+        Self::with_shoe(Shoe::new(num_decks), num_spots, max_penetration)
+    }
+
+    /// Creates a new blackjack table dealing from `shoe` with `num_spots` bettings spots and `max_penetration` (from 0.0-1.0 before shuffling)
+    pub fn with_shoe(shoe: Shoe, num_spots: usize, max_penetration: f32) -> Self {
         let player_hands = vec![Hand::default(); num_spots];
-        let _player_bets = vec![Bet::default(); num_spots];
+        let player_bets = vec![Bet::default(); num_spots];
         let dealer = Hand::default();
 
-        let max_penetration = max_penetration.clamp(0.0, 1.0);
+        let rules = TableRules {
+            decks: shoe.num_decks(),
+            penetration: max_penetration.clamp(0.0, 1.0),
+            ..Default::default()
+        };
 
         Self {
             dealer,
             player_hands,
-            _player_bets,
+            player_bets,
             shoe,
-            max_penetration,
-            num_decks,
+            rules,
+            cut_card_reached: false,
             state: TableState::Open,
+            rounds: 0,
+            record: RoundRecord::default(),
+            history: History::default(),
+            training: false,
+            undo: Vec::new(),
+            redo: Vec::new(),
         }
     }
 
+    /// Creates a new blackjack table played by `rules` with `num_spots` bettings spots
+    pub fn with_rules(rules: TableRules, num_spots: usize) -> Self {
+        let mut table = Self::with_shoe(Shoe::new(rules.decks), num_spots, rules.penetration);
+        table.rules = TableRules {
+            penetration: table.rules.penetration,
+            ..rules
+        };
+        table
+    }
+
+    /// The actions a table plays. Doubling, splitting and surrendering are only played by a
+    /// [Simulator](crate::sim::Simulator).
+    pub const SUPPORTED_ACTIONS: [Action; 2] = [Action::Hit, Action::Stand];
+
+    /// Returns the actions player `player` may take on their hand: those the rules allow that
+    /// the table supports
+    pub fn allowed_actions(&self, player: usize) -> Vec<Action> {
+        let hand = self.player_hand(player);
+        let mut allowed = vec![Action::Stand];
+        if !hand.busted() {
+            allowed.push(Action::Hit);
+        }
+        if hand.len() == 2 {
+            allowed.push(Action::Double);
+            if hand.pairs() && self.rules.max_splits > 0 {
+                allowed.push(Action::Split);
+            }
+            if self.rules.surrender {
+                allowed.push(Action::Surrender);
+            }
+        }
+        allowed.retain(|action| Self::SUPPORTED_ACTIONS.contains(action));
+        allowed
+    }
+
+    /// Returns the rules of the table
+    pub fn rules(&self) -> &TableRules {
+        &self.rules
+    }
+
     /// Resets the table
     ///
     /// # Panics
@@ -79,13 +293,53 @@ impl Table {
             panic!("Cannot reset table while cards are dealt");
         }
 
-        self.shoe = Shoe::new(self.num_decks);
+        self.shoe.reset();
+        self.cut_card_reached = false;
         self.dealer = Hand::default();
         for player in &mut self.player_hands {
             *player = Hand::default();
         }
     }
 
+    /// Returns true if the cut card has come out of the shoe, so the shoe is reshuffled before
+    /// the next round
+    pub fn cut_card_reached(&self) -> bool {
+        self.cut_card_reached
+    }
+
+    /// Subscribe `observer` to the events of the table and its shoe
+    pub fn subscribe(&mut self, observer: impl FnMut(&Event) + Send + 'static) {
+        self.shoe.subscribe(observer);
+    }
+
+    /// Deal a card from the shoe to `seat` (or the dealer when [None]), noting when the cut card comes out
+    fn draw(&mut self, seat: Option<usize>, face_up: bool) -> Card {
+        let card = if face_up {
+            self.shoe.deal()
+        } else {
+            self.shoe.deal_face_down()
+        }
+        .unwrap();
+
+        if !self.cut_card_reached && self.shoe.penetration() > self.rules.penetration {
+            self.cut_card_reached = true;
+            self.shoe.emit(Event::CutCardReached);
+        }
+
+        self.record.steps.push(Step::Card { seat, card });
+        card
+    }
+
+    /// Turn the dealer's hole card face up
+    fn reveal_hole(&mut self) {
+        if self.state == TableState::Dealt {
+            self.state = TableState::Flipped;
+            if let Some(&card) = self.dealer.cards().get(1) {
+                self.shoe.emit(Event::CardRevealed(card));
+            }
+        }
+    }
+
     /// Clears all hands from the table
     ///
     /// # Panics
@@ -94,11 +348,66 @@ impl Table {
         if self.state != TableState::Flipped {
             panic!("Cannot clear hands before dealer has flipped");
         }
+
+        let mut record = std::mem::take(&mut self.record);
+        record.dealer = self.dealer.cards().to_vec();
+        record.seats = (0..self.player_hands.len())
+            .map(|player| {
+                let outcome = self.get_outcome(player);
+                let wager = self.player_bets[player].units();
+                SeatRecord {
+                    wager,
+                    cards: self.player_hands[player].cards().to_vec(),
+                    outcome,
+                    payout: self.payout(outcome, wager),
+                }
+            })
+            .collect();
+        self.history.push(record);
+
         self.dealer = Hand::default();
         for player in &mut self.player_hands {
             *player = Hand::default();
         }
+        for bet in &mut self.player_bets {
+            *bet = Bet::default();
+        }
+        self.undo.clear();
+        self.redo.clear();
         self.state = TableState::Open;
+        self.shoe.emit(Event::RoundSettled);
+    }
+
+    /// The units won (positive) or lost (negative) on a `wager` with `outcome`
+    fn payout(&self, outcome: Outcome, wager: usize) -> f32 {
+        self.rules.payout(outcome, wager as f64) as f32
+    }
+
+    /// Place a bet for player `player`, adding to any bet already placed
+    ///
+    /// # Panics
+    /// Panics if there are cards currently dealt
+    pub fn place_bet(&mut self, player: usize, bet: Bet) {
+        if self.state != TableState::Open {
+            panic!("Cannot place a bet while cards are dealt");
+        }
+        let placed = std::mem::take(&mut self.player_bets[player]);
+        self.player_bets[player] = placed + bet;
+    }
+
+    /// Returns a reference to the bet of player `player`
+    pub fn player_bet(&self, player: usize) -> &Bet {
+        &self.player_bets[player]
+    }
+
+    /// Returns the history of rounds played at the table
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Returns a mutable reference to the history of rounds played at the table
+    pub fn history_mut(&mut self) -> &mut History {
+        &mut self.history
     }
 
     /// Deal the initial hands for player and dealers. Returns true if dealing prompted the shoe to reshuffle.
@@ -114,18 +423,31 @@ impl Table {
             debug_assert!(player.is_empty());
         }
 
-        let reshuffle = self.shoe.penetration() > self.max_penetration;
+        let reshuffle = self.shoe.penetration() > self.rules.penetration;
         if reshuffle {
-            self.shoe = Shoe::new(self.num_decks);
+            self.shoe.reset();
+            self.cut_card_reached = false;
         }
 
         self.state = TableState::Dealt;
-
-        for _ in 0..2 {
-            for player in &mut self.player_hands {
-                player.insert(self.shoe.deal().unwrap());
+        self.rounds += 1;
+        self.record = RoundRecord {
+            round: self.rounds,
+            cards_remaining: self.shoe.len(),
+            penetration: self.shoe.penetration(),
+            running_count: self.shoe.running_count(),
+            true_count: self.shoe.true_count(),
+            ..Default::default()
+        };
+        self.shoe.emit(Event::RoundStarted);
+
+        for round in 0..2 {
+            for player in 0..self.player_hands.len() {
+                let card = self.draw(Some(player), true);
+                self.player_hands[player].insert(card);
             }
-            self.dealer.insert(self.shoe.deal().unwrap());
+            let card = self.draw(None, round == 0);
+            self.dealer.insert(card);
         }
 
         reshuffle
@@ -141,7 +463,7 @@ impl Table {
         }
 
         if self.dealer.blackjack() {
-            self.state = TableState::Flipped;
+            self.reveal_hole();
             true
         } else {
             false
@@ -155,52 +477,133 @@ impl Table {
 
     /// Get the outcome for a player's hand
     pub fn get_outcome(&self, player: usize) -> Outcome {
-        if self.dealer.blackjack() {
-            return if self.player_hand(player).blackjack() {
-                Outcome::Push
-            } else {
-                Outcome::Lose
-            };
-        }
+        Outcome::of(self.player_hand(player), true, &self.dealer)
+    }
 
-        if self.player_hand(player).blackjack() {
-            return Outcome::Blackjack;
+    /// Deal a player an additional card. Returns true if the player busted.
+    ///
+    /// # Panics
+    /// Panics if there are no cards currently dealt
+    pub fn player_hit(&mut self, player: usize) -> bool {
+        self.hit(player, false)
+    }
+
+    /// Deal a player an additional card, recording it as a redo if `redo`
+    fn hit(&mut self, player: usize, redo: bool) -> bool {
+        if self.state != TableState::Dealt {
+            panic!("Cannot hit when no cards are dealt");
         }
 
         if self.player_hand(player).busted() {
-            return Outcome::Lose;
+            return true;
         }
 
-        if self.dealer.busted() {
-            return Outcome::Win;
-        }
+        self.begin_action(player, Action::Hit, redo);
+        let card = self.draw(Some(player), true);
+        self.player_hands[player].insert(card);
 
-        match (
-            self.player_hand(player).value().unwrap(),
-            self.dealer.value().unwrap(),
-        ) {
-            (p, d) if p > d => Outcome::Win,
-            (p, d) if p < d => Outcome::Lose,
-            _ => Outcome::Push,
-        }
+        self.player_hand(player).busted()
     }
 
-    /// Deal a player an additional card. Returns true if the player busted.
+    /// Player `player` stands on their hand
     ///
     /// # Panics
     /// Panics if there are no cards currently dealt
-    pub fn player_hit(&mut self, player: usize) -> bool {
+    pub fn player_stand(&mut self, player: usize) {
+        self.stand(player, false)
+    }
+
+    /// Player `player` stands on their hand, recording it as a redo if `redo`
+    fn stand(&mut self, player: usize, redo: bool) {
         if self.state != TableState::Dealt {
-            panic!("Cannot hit when no cards are dealt");
+            panic!("Cannot stand when no cards are dealt");
         }
 
-        if self.player_hand(player).busted() {
-            return true;
+        self.begin_action(player, Action::Stand, redo);
+    }
+
+    /// Record player `player` taking `action`, taking a snapshot to undo it in training mode
+    fn begin_action(&mut self, player: usize, action: Action, redo: bool) {
+        if self.training {
+            self.undo.push(Snapshot {
+                seat: player,
+                action,
+                hand: self.player_hands[player].clone(),
+                seen: self.shoe.seen().len(),
+            });
         }
 
-        self.player_hands[player].insert(self.shoe.deal().unwrap());
+        let step = if redo {
+            Step::Redo {
+                seat: player,
+                action,
+            }
+        } else {
+            self.redo.clear();
+            Step::Action {
+                seat: player,
+                action,
+            }
+        };
+        self.record.steps.push(step);
+    }
+
+    /// Turn training mode on or off. In training mode, player actions can be undone and redone.
+    pub fn set_training(&mut self, training: bool) {
+        self.training = training;
+        if !training {
+            self.undo.clear();
+            self.redo.clear();
+        }
+    }
 
-        self.player_hand(player).busted()
+    /// Returns true if the table is in training mode
+    pub fn training(&self) -> bool {
+        self.training
+    }
+
+    /// Undo the last player action of the round in training mode, returning any cards
+    /// dealt to the top of the shoe. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if self.state != TableState::Dealt {
+            return false;
+        }
+        let Some(snapshot) = self.undo.pop() else {
+            return false;
+        };
+
+        while self.shoe.seen().len() > snapshot.seen {
+            self.shoe.undeal();
+        }
+        if self.shoe.penetration() <= self.rules.penetration {
+            self.cut_card_reached = false;
+        }
+        self.player_hands[snapshot.seat] = snapshot.hand;
+        self.record.steps.push(Step::Undo {
+            seat: snapshot.seat,
+            action: snapshot.action,
+        });
+        self.redo.push((snapshot.seat, snapshot.action));
+        true
+    }
+
+    /// Redo the last undone player action in training mode. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        if self.state != TableState::Dealt {
+            return false;
+        }
+        let Some((seat, action)) = self.redo.pop() else {
+            return false;
+        };
+
+        match action {
+            Action::Hit => {
+                self.hit(seat, true);
+            }
+            Action::Stand => self.stand(seat, true),
+            _ => panic!("Cannot redo {action}"),
+        }
+        true
     }
 
     /// The dealer hits. Returns true if the dealer busted.
@@ -213,12 +616,38 @@ impl Table {
             return true;
         }
 
-        self.dealer.insert(self.shoe.deal().unwrap());
+        self.reveal_hole();
+        let card = self.draw(None, true);
+        self.dealer.insert(card);
 
-        self.state = TableState::Flipped;
         self.dealer.busted()
     }
 
+    /// The dealer draws to their hand by the rules of the table
+    ///
+    /// # Panics
+    /// Panics if there are no cards currently dealt
+    pub fn dealer_play(&mut self) {
+        if self.state == TableState::Open {
+            panic!("Cannot play dealer when no cards are dealt");
+        }
+
+        self.reveal_hole();
+        while self.rules.dealer_hits(&self.dealer) {
+            self.dealer_hit();
+        }
+    }
+
+    /// The dealer's hand, including the hole card
+    pub fn dealer_hand(&self) -> &Hand {
+        &self.dealer
+    }
+
+    /// The dealer's face up card
+    pub fn dealer_upcard(&self) -> Option<Card> {
+        self.dealer.cards().first().copied()
+    }
+
     /// The dealer value
     pub fn dealer_value(&self) -> Option<u8> {
         if self.state == TableState::Open {
@@ -233,28 +662,22 @@ impl Table {
         self.player_hands.iter()
     }
 
+    /// Returns a reference to the shoe
+    pub fn shoe(&self) -> &Shoe {
+        &self.shoe
+    }
+
     /// Flip the dealer's hole card
     pub fn flip_hole(&mut self) {
         if self.state == TableState::Open {
             panic!("Cannot flip hole card when no cards are dealt");
         }
-        self.state = TableState::Flipped;
+        self.reveal_hole();
     }
 }
 
 impl Display for Table {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "\n\n")?;
-        writeln!(
-            f,
-            "Dealer: {}    {} {}",
-            self.dealer,
-            self.shoe.running_count(),
-            self.shoe.penetration()
-        )?;
-        for (i, player) in self.player_hands().enumerate() {
-            writeln!(f, "Player {}: {}", i + 1, player)?;
-        }
-        Ok(())
+        write!(f, "{}", Renderer::default().table(self))
     }
 }
@@ -0,0 +1,173 @@
+/*!
+
+  Shuffle tracking
+
+  Tools to follow slugs of high or low cards from the discard tray through a
+  [ShuffleProcedure] and into the next shoe. Counts use the hi-lo tags of [Card::count].
+
+*/
+
+use crate::{card::Card, shoe::Shoe, shuffle::ShuffleProcedure};
+use rand::Rng;
+
+/// A contiguous run of cards in a pack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment {
+    /// The position of the first card, where 0 is the top of the pack
+    pub start: usize,
+    /// The number of cards in the segment
+    pub len: usize,
+    /// The hi-lo running count of the cards in the segment
+    pub count: i32,
+}
+
+/// Whether a slug is rich in high or low cards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SlugKind {
+    /// Mostly tens and aces (negative count)
+    High,
+    /// Mostly small cards (positive count)
+    Low,
+}
+
+/// A segment of the previous shoe with an extreme count, and where it is expected to land
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Slug {
+    /// The segment of the pack before the shuffle
+    pub segment: Segment,
+    /// Whether the slug holds high or low cards
+    pub kind: SlugKind,
+    /// The mean position of the slug's cards in the next shoe, where 0 is dealt first
+    pub landing: f32,
+    /// The standard deviation of the positions of the slug's cards in the next shoe
+    pub spread: f32,
+}
+
+/// The result of tracking a shoe through a shuffle
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrackingEstimate {
+    /// The slugs found before the shuffle
+    pub slugs: Vec<Slug>,
+    /// The expected hi-lo count of each segment of the next shoe, in dealing order
+    pub expected_counts: Vec<f32>,
+}
+
+/// Splits a pack into segments of `size` cards (the last may be shorter)
+pub fn segments(pack: &[Card], size: usize) -> Vec<Segment> {
+    pack.chunks(size.max(1))
+        .enumerate()
+        .map(|(i, chunk)| Segment {
+            start: i * size.max(1),
+            len: chunk.len(),
+            count: chunk.iter().map(|c| c.count() as i32).sum(),
+        })
+        .collect()
+}
+
+/// Tracks slugs of cards through a shuffle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlugTracker {
+    /// The number of cards per segment
+    segment_size: usize,
+    /// The minimum absolute count of a segment to be considered a slug
+    threshold: i32,
+}
+
+impl SlugTracker {
+    /// Create a tracker dividing packs into segments of `segment_size` cards.
+    /// A segment is a slug when the absolute value of its count is at least `threshold`.
+    pub fn new(segment_size: usize, threshold: i32) -> Self {
+        Self {
+            segment_size: segment_size.max(1),
+            threshold,
+        }
+    }
+
+    /// Returns the segments of the pack `shoe` would be reshuffled from: the stub on top of the discards
+    pub fn pack_segments(&self, shoe: &Shoe) -> Vec<Segment> {
+        segments(&shoe.pack(), self.segment_size)
+    }
+
+    /// Returns the segments of the cards remaining in `shoe`, in dealing order
+    pub fn shoe_segments(&self, shoe: &Shoe) -> Vec<Segment> {
+        let upcoming: Vec<Card> = shoe.upcoming().collect();
+        segments(&upcoming, self.segment_size)
+    }
+
+    /// Estimate where the slugs of `shoe` land when it is reshuffled with `procedure`,
+    /// by performing the procedure `trials` times on the positions of the pack
+    pub fn estimate(
+        &self,
+        shoe: &Shoe,
+        procedure: &impl ShuffleProcedure,
+        trials: usize,
+        rng: &mut impl Rng,
+    ) -> TrackingEstimate {
+        let pack = shoe.pack();
+        let slug_segments: Vec<(Segment, SlugKind)> = segments(&pack, self.segment_size)
+            .into_iter()
+            .filter(|s| s.count.abs() >= self.threshold)
+            .map(|s| {
+                let kind = if s.count < 0 {
+                    SlugKind::High
+                } else {
+                    SlugKind::Low
+                };
+                (s, kind)
+            })
+            .collect();
+
+        let num_segments = pack.len().div_ceil(self.segment_size);
+        let mut count_sums = vec![0.0f64; num_segments];
+        let mut landing_sums = vec![(0.0f64, 0.0f64); slug_segments.len()];
+
+        for _ in 0..trials {
+            let mut order: Vec<usize> = (0..pack.len()).collect();
+            procedure.apply(&mut order, rng);
+
+            let mut position = vec![0; pack.len()];
+            for (i, &p) in order.iter().enumerate() {
+                position[p] = i;
+                count_sums[i / self.segment_size] += pack[p].count() as f64;
+            }
+
+            for ((segment, _), (sum, sum_sq)) in slug_segments.iter().zip(&mut landing_sums) {
+                for &p in &position[segment.start..segment.start + segment.len] {
+                    let x = p as f64;
+                    *sum += x;
+                    *sum_sq += x * x;
+                }
+            }
+        }
+
+        let trials = trials.max(1) as f64;
+        let slugs = slug_segments
+            .into_iter()
+            .zip(landing_sums)
+            .map(|((segment, kind), (sum, sum_sq))| {
+                let n = trials * segment.len as f64;
+                let mean = sum / n;
+                let var = (sum_sq / n - mean * mean).max(0.0);
+                Slug {
+                    segment,
+                    kind,
+                    landing: mean as f32,
+                    spread: var.sqrt() as f32,
+                }
+            })
+            .collect();
+
+        TrackingEstimate {
+            slugs,
+            expected_counts: count_sums
+                .into_iter()
+                .map(|c| (c / trials) as f32)
+                .collect(),
+        }
+    }
+}
@@ -19,3 +19,22 @@ fn test_bet_sub_fail() {
     let bet = Bet::from(Chip::TwentyFive) + Bet::from(Chip::Five) + Bet::default();
     let _bet = bet - Bet::from(Chip::One);
 }
+
+#[test]
+fn test_bet_from_units() {
+    let bet = Bet::from_units(137);
+    assert_eq!(bet.units(), 137);
+    let chips: Vec<Chip> = bet.into_iter().collect();
+    assert_eq!(
+        chips,
+        [
+            Chip::Hundred,
+            Chip::TwentyFive,
+            Chip::Five,
+            Chip::Five,
+            Chip::One,
+            Chip::One
+        ]
+    );
+    assert!(Bet::from_units(0).is_empty());
+}
@@ -0,0 +1,104 @@
+use shoo::{
+    Card, Chip, Counter, Rank, ShoeBuilder, Suit,
+    betting::{BettingStrategy, Flat, Kelly, TrueCountRamp},
+    sim::Simulator,
+    table::TableRules,
+};
+
+fn ramp() -> TrueCountRamp {
+    TrueCountRamp::new(1)
+        .step(4.0, 8)
+        .step(2.0, 2)
+        .step(5.0, 12)
+        .step(3.0, 4)
+}
+
+#[test]
+fn test_true_count_ramp() {
+    let ramp = ramp();
+    assert_eq!(ramp.units(-3.0), 1);
+    assert_eq!(ramp.units(1.9), 1);
+    assert_eq!(ramp.units(2.0), 2);
+    assert_eq!(ramp.units(3.5), 4);
+    assert_eq!(ramp.units(4.0), 8);
+    assert_eq!(ramp.units(9.0), 12);
+    assert_eq!(ramp.spread(), Some(12.0));
+    assert_eq!(TrueCountRamp::new(0).step(1.0, 4).spread(), None);
+
+    // Dealing 52 small cards from two decks leaves a true count of +52
+    let low = Card::new(Suit::Heart, Rank::Two);
+    let mut shoe = ShoeBuilder::new()
+        .add_card(low, 52)
+        .decks(1)
+        .stacked()
+        .build();
+    assert_eq!(ramp.bet(shoe.true_count(), 100.0).units(), 1);
+    for _ in 0..52 {
+        shoe.deal();
+    }
+    assert_eq!(shoe.true_count(), 52.0);
+    let bet = ramp.bet(shoe.true_count(), 100.0);
+    assert_eq!(bet.units(), 12);
+    assert_eq!(
+        bet.into_iter().collect::<Vec<_>>(),
+        [Chip::Five, Chip::Five, Chip::One, Chip::One]
+    );
+}
+
+#[test]
+fn test_kelly() {
+    let kelly = Kelly::new(1.0).variance(1.0).limits(5, 200);
+    assert!((kelly.advantage_at(3.0) - 0.01).abs() < 1e-9);
+    assert_eq!(kelly.units(0.0, 10_000.0), 5);
+    assert_eq!(kelly.units(3.0, 10_050.0), 100);
+    assert_eq!(kelly.units(20.0, 10_000.0), 200);
+
+    let half = Kelly::new(0.5).variance(1.0).limits(1, 1000);
+    assert_eq!(half.units(3.0, 10_050.0), 50);
+    assert_eq!(half.units(3.0, 5_050.0), 25);
+}
+
+#[test]
+fn test_simulate_spread() {
+    let rules = TableRules::default();
+    let flat = Simulator::new(rules).seed(6).play_rounds(5_000);
+    let spread = Simulator::new(rules)
+        .seed(6)
+        .with_betting(ramp())
+        .play_rounds(5_000);
+    assert_eq!(flat.wagered(), 5_000.0);
+    assert!(spread.wagered() > flat.wagered());
+    assert_eq!(Flat::new(30).bet(10.0, 0.0).units(), 30);
+}
+
+/// A counter that counts nothing, so its true count never moves
+struct Uncounted;
+
+impl Counter for Uncounted {
+    fn new(_num_decks: usize) -> Self {
+        Uncounted
+    }
+
+    fn clear(&mut self) {}
+
+    fn count(&self) -> f32 {
+        0.0
+    }
+
+    fn insert(&mut self, _card: Card) {}
+
+    fn tag(_rank: Rank) -> f32 {
+        0.0
+    }
+}
+
+#[test]
+fn test_simulate_counter() {
+    let mut sim = Simulator::new(TableRules::default())
+        .seed(6)
+        .with_betting(ramp())
+        .with_counter::<Uncounted>();
+    let stats = sim.play_rounds(5_000);
+    assert_eq!(sim.true_count(), 0.0);
+    assert_eq!(stats.wagered(), 5_000.0);
+}
@@ -0,0 +1,53 @@
+use shoo::{Composition, Deck, Hand, Rank, ShoeBuilder};
+use std::collections::HashMap;
+
+#[test]
+fn test_full_decks() {
+    let composition = Composition::decks(6);
+    assert_eq!(composition.len(), 312);
+    assert_eq!(composition.count(Rank::Ace), 24);
+    assert_eq!(composition.count(Rank::King), 96);
+    assert_eq!(composition.probability(Rank::Jack), 96.0 / 312.0);
+    assert_eq!(Composition::from(&Deck::new()), Composition::decks(1));
+    assert_eq!(
+        composition
+            .iter()
+            .map(|(_, count)| count)
+            .collect::<Vec<_>>(),
+        [24, 24, 24, 24, 24, 24, 24, 24, 24, 96]
+    );
+}
+
+#[test]
+fn test_add_remove() {
+    let mut composition = Composition::new();
+    assert!(composition.is_empty());
+    assert!(!composition.remove(Rank::Five));
+    composition.add(Rank::Five, 2);
+    assert!(composition.remove(Rank::Five));
+    assert_eq!(composition.count(Rank::Five), 1);
+    assert_eq!(composition.without(Rank::Five).unwrap().len(), 0);
+    assert_eq!(composition.without(Rank::Six), None);
+    assert_eq!(composition.probability(Rank::Six), 0.0);
+}
+
+#[test]
+fn test_from_shoe_and_hand() {
+    let mut shoe = ShoeBuilder::new().decks(1).seed(4).build();
+    let dealt: Composition = (0..10).filter_map(|_| shoe.deal()).collect();
+    let remaining = Composition::from(&shoe);
+    assert_eq!(remaining.len(), 42);
+    for (rank, count) in Composition::decks(1).iter() {
+        assert_eq!(remaining.count(rank) + dealt.count(rank), count);
+    }
+
+    let hand: Hand = "Ks Qh 5d".parse().unwrap();
+    let composition = Composition::from(&hand);
+    assert_eq!(composition.count(Rank::Ten), 2);
+    assert_eq!(composition.len(), 3);
+
+    let mut cache = HashMap::new();
+    cache.insert(composition, 1);
+    let same: Hand = "Th 5c Js".parse().unwrap();
+    assert_eq!(cache.get(&Composition::from(&same)), Some(&1));
+}
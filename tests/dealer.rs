@@ -0,0 +1,75 @@
+use shoo::{
+    Composition, Rank,
+    dealer::{DealerOdds, DealerOutcome},
+    table::TableRules,
+};
+
+fn unseen(upcard: Rank) -> Composition {
+    Composition::decks(6).without(upcard).unwrap()
+}
+
+#[test]
+fn test_probabilities_sum_to_one() {
+    for upcard in Composition::new().iter().map(|(rank, _)| rank) {
+        for (hits_soft_17, peek) in [(false, false), (false, true), (true, false), (true, true)] {
+            let rules = TableRules {
+                dealer_hits_soft_17: hits_soft_17,
+                peek,
+                ..Default::default()
+            };
+            let odds = DealerOdds::new(upcard, &unseen(upcard), rules);
+            let total: f64 = odds.iter().map(|(_, p)| p).sum();
+            assert!((total - 1.0).abs() < 1e-9, "{upcard} {total}");
+        }
+    }
+}
+
+#[test]
+fn test_peek() {
+    let no_peek = TableRules {
+        peek: false,
+        ..Default::default()
+    };
+    let odds = DealerOdds::new(Rank::Ace, &unseen(Rank::Ace), no_peek);
+    assert!((odds.blackjack() - 96.0 / 311.0).abs() < 1e-12);
+    let odds = DealerOdds::new(Rank::King, &unseen(Rank::King), no_peek);
+    assert!((odds.blackjack() - 24.0 / 311.0).abs() < 1e-12);
+
+    let peeked = DealerOdds::new(Rank::Ace, &unseen(Rank::Ace), TableRules::default());
+    assert_eq!(peeked.blackjack(), 0.0);
+    let odds = DealerOdds::new(Rank::Ace, &unseen(Rank::Ace), no_peek);
+    let scaled = odds.bust() / (1.0 - odds.blackjack());
+    assert!((peeked.bust() - scaled).abs() < 1e-12);
+}
+
+#[test]
+fn test_soft_17() {
+    let h17 = TableRules {
+        dealer_hits_soft_17: true,
+        ..Default::default()
+    };
+    let stands = DealerOdds::new(Rank::Six, &unseen(Rank::Six), TableRules::default());
+    let hits = DealerOdds::new(Rank::Six, &unseen(Rank::Six), h17);
+    assert!(hits.bust() > stands.bust());
+    assert!(
+        hits.probability(DealerOutcome::Seventeen) < stands.probability(DealerOutcome::Seventeen)
+    );
+    // The dealer busts a 6 42.28% of the time in a 6-deck shoe, and 42.32% of the time in
+    // an infinite shoe
+    assert!((stands.bust() - 0.4228).abs() < 0.0001, "{}", stands.bust());
+    let infinite = Composition::decks(1000).without(Rank::Six).unwrap();
+    let odds = DealerOdds::new(Rank::Six, &infinite, TableRules::default());
+    assert!((odds.bust() - 0.4232).abs() < 0.0005, "{}", odds.bust());
+    assert_eq!(DealerOutcome::Bust.total(), None);
+    assert_eq!(DealerOutcome::Nineteen.to_string(), "19");
+}
+
+#[test]
+fn test_small_composition() {
+    let mut unseen = Composition::new();
+    unseen.add(Rank::Ten, 1);
+    unseen.add(Rank::Seven, 1);
+    let odds = DealerOdds::new(Rank::Queen, &unseen, TableRules::default());
+    assert_eq!(odds.probability(DealerOutcome::Twenty), 0.5);
+    assert_eq!(odds.probability(DealerOutcome::Seventeen), 0.5);
+}
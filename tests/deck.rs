@@ -0,0 +1,42 @@
+use rand::{SeedableRng, rngs::StdRng};
+use shoo::{Card, Deck, Rank, ShoeBuilder, Suit};
+use std::collections::HashSet;
+
+#[test]
+fn test_all_ranks_and_suits() {
+    assert_eq!(Rank::iter().count(), 13);
+    assert_eq!(Suit::iter().count(), 4);
+    assert_eq!(Rank::ALL.first(), Some(&Rank::Ace));
+    assert_eq!(Rank::ALL.last(), Some(&Rank::King));
+    assert_eq!(
+        Rank::iter()
+            .filter(|r| r.is_ten_value())
+            .collect::<Vec<_>>(),
+        Rank::ten_value()
+    );
+    assert!(!Rank::Ace.is_ten_value());
+
+    let cards: HashSet<Card> = Card::iter().collect();
+    assert_eq!(cards.len(), 52);
+}
+
+#[test]
+fn test_deck() {
+    let mut deck = Deck::new();
+    assert_eq!(deck.len(), 52);
+    assert_eq!(deck[0], Card::new(Suit::Heart, Rank::Ace));
+
+    let mut rng = StdRng::seed_from_u64(1);
+    deck.shuffle(&mut rng);
+    assert_ne!(deck, Deck::new());
+    deck.sort();
+    assert_eq!(deck, Deck::new());
+
+    let top = deck.deal();
+    assert_eq!(top, Some(Card::new(Suit::Heart, Rank::Ace)));
+    assert_eq!(deck.len(), 51);
+    assert!(!deck.cards().contains(&top.unwrap()));
+
+    let shoe = ShoeBuilder::new().cards(deck).build();
+    assert_eq!(shoe.len(), 51);
+}
@@ -0,0 +1,338 @@
+use shoo::{
+    Card, Composition, Counter, Hand, HandClass, HiLoCounter, Rank, Suit,
+    dealer::{DealerOdds, DealerOutcome},
+    ev::{Analyzer, HouseEdge, Rule},
+    strategy::{BasicStrategy, ChartEntry, ChartRow},
+    table::{Action, TableRules},
+};
+
+/// Returns `hand` and the six-deck shoe without its cards and `upcard`
+fn deal(hand: &str, upcard: Rank) -> (Hand, Composition) {
+    let hand: Hand = hand.parse().unwrap();
+    let mut unseen = Composition::decks(6);
+    for card in hand.cards() {
+        unseen.remove(card.rank());
+    }
+    unseen.remove(upcard);
+    (hand, unseen)
+}
+
+#[test]
+fn test_best_actions() {
+    let mut analyzer = Analyzer::new(TableRules::default());
+    let (hand, unseen) = deal("10h 6c", Rank::King);
+    let evs = analyzer.evaluate(&hand, Rank::King, &unseen);
+    assert_eq!(evs.best(), (Action::Surrender, -0.5));
+    assert!(evs.get(Action::Hit).unwrap() > evs.get(Action::Stand).unwrap());
+    assert_eq!(evs.get(Action::Split), None);
+    assert_eq!(evs.cost(Action::Surrender), Some(0.0));
+
+    let (hand, unseen) = deal("6h 5c", Rank::Six);
+    let evs = analyzer.evaluate(&hand, Rank::Six, &unseen);
+    assert_eq!(evs.best().0, Action::Double);
+    assert!((evs.best().1 - 0.683).abs() < 0.001, "{}", evs.best().1);
+
+    let (hand, unseen) = deal("8h 8c", Rank::Ten);
+    let evs = analyzer.evaluate(&hand, Rank::Ten, &unseen);
+    assert_eq!(evs.best().0, Action::Split);
+    assert_eq!(evs.iter().count(), 5);
+
+    let (hand, unseen) = deal("5h 5c", Rank::Nine);
+    let evs = analyzer.evaluate(&hand, Rank::Nine, &unseen);
+    assert_eq!(evs.best().0, Action::Double);
+}
+
+#[test]
+fn test_stand_matches_dealer_odds() {
+    let mut analyzer = Analyzer::new(TableRules::default());
+    let (hand, unseen) = deal("10h 5c 2d", Rank::Seven);
+    let evs = analyzer.evaluate(&hand, Rank::Seven, &unseen);
+    assert_eq!(
+        evs.iter().map(|(action, _)| action).collect::<Vec<_>>(),
+        [Action::Hit, Action::Stand]
+    );
+
+    let odds = DealerOdds::new(Rank::Seven, &unseen, TableRules::default());
+    let expected = odds.bust()
+        - DealerOutcome::iter()
+            .filter(|outcome| outcome.total().is_some_and(|total| total > 17))
+            .map(|outcome| odds.probability(outcome))
+            .sum::<f64>();
+    assert!((evs.get(Action::Stand).unwrap() - expected).abs() < 1e-12);
+}
+
+#[test]
+fn test_no_peek() {
+    let no_peek = TableRules {
+        peek: false,
+        ..Default::default()
+    };
+    let (hand, unseen) = deal("6h 5c", Rank::Ace);
+    let peeked = Analyzer::new(TableRules::default()).evaluate(&hand, Rank::Ace, &unseen);
+    let evs = Analyzer::new(no_peek).evaluate(&hand, Rank::Ace, &unseen);
+    let blackjack = unseen.probability(Rank::Ten);
+    let double = peeked.get(Action::Double).unwrap();
+    assert!(
+        (evs.get(Action::Double).unwrap() - ((1.0 - blackjack) * double - 2.0 * blackjack)).abs()
+            < 1e-12
+    );
+
+    let (hand, unseen) = deal("Ah Kc", Rank::Ace);
+    let evs = Analyzer::new(no_peek).evaluate(&hand, Rank::Ace, &unseen);
+    let blackjack = unseen.probability(Rank::Ten);
+    assert!((evs.get(Action::Stand).unwrap() - 1.5 * (1.0 - blackjack)).abs() < 1e-12);
+}
+
+#[test]
+fn test_peeked_hole_card() {
+    // The peek rules out the ten under the ace, so the hole card is a 9 and the 11 draws the
+    // ten or a 9 for 20 against the dealer's soft 20
+    let hand: Hand = "6h 5c".parse().unwrap();
+    let mut unseen = Composition::new();
+    unseen.add(Rank::Ten, 1);
+    unseen.add(Rank::Nine, 2);
+    let evs = Analyzer::new(TableRules::default()).evaluate(&hand, Rank::Ace, &unseen);
+    assert!((evs.get(Action::Double).unwrap() - 1.0).abs() < 1e-12);
+    assert!((evs.get(Action::Hit).unwrap() - 0.5).abs() < 1e-12);
+    assert!((evs.get(Action::Stand).unwrap() + 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_split_rules() {
+    let (hand, unseen) = deal("Ah Ac", Rank::Six);
+    let mut rules = TableRules::default();
+    let split = |rules| {
+        Analyzer::new(rules)
+            .evaluate(&hand, Rank::Six, &unseen)
+            .get(Action::Split)
+    };
+    let base = split(rules).unwrap();
+    rules.resplit_aces = true;
+    assert!(split(rules).unwrap() > base);
+    rules.max_splits = 0;
+    assert_eq!(split(rules), None);
+}
+
+#[test]
+fn test_generated_charts() {
+    let mut analyzer = Analyzer::new(TableRules::default());
+    let shoe = Composition::decks(6);
+    let chart = analyzer.composition_dependent_chart(&shoe);
+    let total_dependent = analyzer.total_dependent_chart(&shoe);
+    assert_eq!(total_dependent.card_rows().count(), 0);
+    assert!(chart.rows().eq(total_dependent.rows()));
+
+    // The derived totals match the published chart, apart from doubling soft 12 against a 6
+    let published = BasicStrategy::default();
+    for (row, entries) in chart.rows() {
+        for (column, rank) in Rank::iter().skip(1).take(9).chain([Rank::Ace]).enumerate() {
+            let upcard = Card::new(Suit::Heart, rank);
+            let expected = published.chart().get(row, upcard).unwrap();
+            if row == ChartRow::Soft(12) && rank == Rank::Six {
+                assert_eq!(entries[column], ChartEntry::DoubleHit);
+            } else {
+                assert_eq!(entries[column], expected, "{row} against {rank}");
+            }
+        }
+    }
+
+    // 10-2 stands against a 4 under total-dependent strategy, but the ten it holds makes
+    // hitting better
+    let four = Card::new(Suit::Heart, Rank::Four);
+    assert_eq!(
+        chart.get_cards([Rank::Ten, Rank::Two], four),
+        Some(ChartEntry::Hit)
+    );
+    assert_eq!(chart.get(ChartRow::Hard(12), four), Some(ChartEntry::Stand));
+}
+
+#[test]
+fn test_house_edge() {
+    let rules = TableRules {
+        blackjack_payout: 1.2,
+        surrender: false,
+        double_after_split: false,
+        penetration: 1.0,
+        ..Default::default()
+    };
+    let edge = HouseEdge::new(rules);
+    // About a third of a percent under the default rules
+    assert!(
+        (0.0025..0.0040).contains(&edge.baseline()),
+        "{}",
+        edge.baseline()
+    );
+
+    // 6:5 pays 0.3 units less on the 4.5% of rounds with a player blackjack
+    let payout = edge.contribution(Rule::BlackjackPayout);
+    assert!((0.0130..0.0140).contains(&payout), "{payout}");
+    let surrender = edge.contribution(Rule::Surrender);
+    assert!((0.0003..0.0015).contains(&surrender), "{surrender}");
+    let das = edge.contribution(Rule::DoubleAfterSplit);
+    assert!((0.0010..0.0020).contains(&das), "{das}");
+    for rule in [
+        Rule::Decks,
+        Rule::DealerHitsSoft17,
+        Rule::Peek,
+        Rule::MaxSplits,
+    ] {
+        assert_eq!(edge.contribution(rule), 0.0);
+    }
+
+    let total = edge.contributions().map(|(_, c)| c).sum::<f64>();
+    assert!((edge.baseline() + total - edge.edge()).abs() < 1e-12);
+}
+
+#[test]
+#[should_panic(expected = "without a cut card")]
+fn test_house_edge_cut_card() {
+    HouseEdge::new(TableRules::default());
+}
+
+/// Counts with the hi-lo tags reversed
+struct Reversed(HiLoCounter);
+
+impl Counter for Reversed {
+    fn new(num_decks: usize) -> Self {
+        Self(HiLoCounter::new(num_decks))
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    fn count(&self) -> f32 {
+        -self.0.count()
+    }
+
+    fn insert(&mut self, card: Card) {
+        self.0.insert(card);
+    }
+
+    fn tag(rank: Rank) -> f32 {
+        -HiLoCounter::tag(rank)
+    }
+}
+
+#[test]
+fn test_approximate() {
+    let edge = Analyzer::new(TableRules::default())
+        .approximate()
+        .house_edge(&Composition::decks(6));
+    assert!((0.0030..0.0036).contains(&edge), "{edge}");
+}
+
+#[test]
+fn test_approximate_after_exact() {
+    // Hitting 7-3 and drawing a 4 from a full deck leaves a 14 and the deck without a 4,
+    // which hitting 7-5 and drawing a 2 reaches without removing drawn cards
+    let ten: Hand = "7h 3c".parse().unwrap();
+    let twelve: Hand = "7h 5c".parse().unwrap();
+    let deck = Composition::decks(1);
+    let without_four = deck.without(Rank::Four).unwrap();
+    let mut analyzer = Analyzer::new(TableRules::default());
+    analyzer.evaluate(&ten, Rank::Ten, &deck);
+    let mut analyzer = analyzer.approximate();
+    let after = analyzer.evaluate(&twelve, Rank::Ten, &without_four);
+    let fresh = Analyzer::new(TableRules::default()).approximate().evaluate(
+        &twelve,
+        Rank::Ten,
+        &without_four,
+    );
+    let hit = after.get(Action::Hit).unwrap() - fresh.get(Action::Hit).unwrap();
+    assert!(hit.abs() < 1e-12, "{hit}");
+}
+
+#[test]
+fn test_effects_of_removal() {
+    let rules = TableRules {
+        decks: 1,
+        surrender: false,
+        ..Default::default()
+    };
+    let effects = Analyzer::new(rules)
+        .approximate()
+        .effects_of_removal(&Composition::decks(1));
+    assert_eq!(effects.iter().count(), 10);
+    let edge = Analyzer::new(rules)
+        .approximate()
+        .house_edge(&Composition::decks(1));
+    assert!((effects.ev() + edge).abs() < 1e-12);
+    assert_eq!(effects.get(Rank::King), effects.get(Rank::Ten));
+
+    // Small cards help the dealer and big cards the player, the five most of all
+    for (rank, effect) in effects.iter() {
+        match rank.value() {
+            2..=7 => assert!(effect > 0.002, "{rank} {effect}"),
+            8 => assert!(effect.abs() < 0.001, "{rank} {effect}"),
+            _ => assert!(effect < -0.001, "{rank} {effect}"),
+        }
+    }
+    let five = effects.get(Rank::Five);
+    assert!((0.0065..0.0085).contains(&five), "{five}");
+    assert!(effects.iter().all(|(_, effect)| effect <= five));
+
+    // Removing a card at random leaves the expected value about the same
+    let mean = effects
+        .iter()
+        .map(|(rank, effect)| effect * if rank == Rank::Ten { 16.0 } else { 4.0 })
+        .sum::<f64>()
+        / 52.0;
+    assert!(mean.abs() < 0.0002, "{mean}");
+
+    assert_eq!(HiLoCounter::tag(Rank::Five), 1.0);
+    assert_eq!(HiLoCounter::tag(Rank::Eight), 0.0);
+    assert_eq!(Reversed::tag(Rank::Queen), 1.0);
+    let correlation = effects.correlation::<HiLoCounter>();
+    assert!((0.95..0.98).contains(&correlation), "{correlation}");
+    assert!((effects.correlation::<Reversed>() + correlation).abs() < 1e-12);
+}
+
+#[test]
+fn test_indices() {
+    let rules = TableRules {
+        surrender: false,
+        ..Default::default()
+    };
+    let shoe = Composition::decks(6);
+    let mut analyzer = Analyzer::new(rules).approximate();
+    let indices = analyzer.indices::<HiLoCounter>(&shoe);
+    let index = |row, upcard| {
+        let mut found = indices.get(row, upcard);
+        let index = *found.next().unwrap();
+        assert!(found.next().is_none());
+        (index.deviation, index.true_count, index.above)
+    };
+
+    let (deviation, true_count, above) = index(HandClass::Hard(16), Rank::King);
+    assert_eq!((deviation, above), (Action::Stand, true));
+    assert!((-0.5..1.5).contains(&true_count), "{true_count}");
+    let (deviation, true_count, above) = index(HandClass::Hard(12), Rank::Two);
+    assert_eq!((deviation, above), (Action::Stand, true));
+    assert!((2.0..4.0).contains(&true_count), "{true_count}");
+    let (deviation, true_count, above) = index(HandClass::Hard(12), Rank::Four);
+    assert_eq!((deviation, above), (Action::Hit, false));
+    assert!((-1.5..0.5).contains(&true_count), "{true_count}");
+    let (deviation, true_count, above) = index(HandClass::Pair(Rank::Ten), Rank::Six);
+    assert_eq!((deviation, above), (Action::Split, true));
+    assert!((3.5..5.5).contains(&true_count), "{true_count}");
+    assert!(
+        indices
+            .iter()
+            .all(|index| index.true_count.abs() <= 10.0 && index.deviation != index.basic)
+    );
+
+    // Reversing the tags reverses every index
+    let reversed = analyzer.indices::<Reversed>(&shoe);
+    assert_eq!(reversed.iter().count(), indices.iter().count());
+    for (index, reversed) in indices.iter().zip(reversed.iter()) {
+        assert_eq!(index.deviation, reversed.deviation);
+        assert_eq!(index.above, !reversed.above);
+        assert!((index.true_count + reversed.true_count).abs() < 1e-4);
+    }
+    assert!(
+        indices
+            .to_string()
+            .contains("Hard16 vs 10: Stand instead of Hit at +")
+    );
+}
@@ -0,0 +1,81 @@
+use std::sync::{Arc, Mutex};
+
+use shoo::{Event, ShoeBuilder, table::Table};
+
+fn record(table: &mut Table) -> Arc<Mutex<Vec<Event>>> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    table.subscribe(move |event| sink.lock().unwrap().push(*event));
+    events
+}
+
+#[test]
+fn test_round_events() {
+    let mut table = Table::new(1, 2, 0.75);
+    let events = record(&mut table);
+
+    table.deal();
+    table.flip_hole();
+    table.clear_hands();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.first(), Some(&Event::RoundStarted));
+    assert_eq!(events.last(), Some(&Event::RoundSettled));
+
+    let dealt: Vec<bool> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::CardDealt { face_up, .. } => Some(*face_up),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(dealt, [true, true, true, true, true, false]);
+
+    let hole = hole_card(&events);
+    assert!(events.contains(&Event::CardRevealed(hole)));
+}
+
+fn hole_card(events: &[Event]) -> shoo::Card {
+    events
+        .iter()
+        .find_map(|e| match e {
+            Event::CardDealt {
+                card,
+                face_up: false,
+            } => Some(*card),
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_cut_card_and_shuffle_events() {
+    let shoe = ShoeBuilder::new().decks(1).seed(1).build();
+    let mut table = Table::with_shoe(shoe, 1, 0.5);
+    let events = record(&mut table);
+
+    let mut reshuffled = false;
+    while !reshuffled {
+        reshuffled = table.deal();
+        table.flip_hole();
+        table.clear_hands();
+    }
+
+    let events = events.lock().unwrap();
+    let cut = events
+        .iter()
+        .position(|e| *e == Event::CutCardReached)
+        .unwrap();
+    let shuffle = events
+        .iter()
+        .position(|e| *e == Event::ShuffleStarted)
+        .unwrap();
+    assert!(cut < shuffle);
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| **e == Event::CutCardReached)
+            .count(),
+        1
+    );
+}
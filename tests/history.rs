@@ -0,0 +1,168 @@
+use shoo::{
+    Bet, Chip, ShoeBuilder,
+    history::{History, RoundRecord, Step, from_text, to_text},
+    table::{Outcome, Table},
+};
+
+fn play_round(table: &mut Table) {
+    table.place_bet(0, Bet::from(Chip::TwentyFive));
+    table.place_bet(1, Bet::from(Chip::Five));
+    table.deal();
+    if !table.peek() {
+        if table.player_hand(0).value().is_some_and(|v| v < 12) {
+            table.player_hit(0);
+        }
+        table.player_stand(0);
+        table.player_stand(1);
+        table.flip_hole();
+        while table.dealer_value().is_some_and(|v| v < 17) {
+            table.dealer_hit();
+        }
+    }
+    table.clear_hands();
+}
+
+#[test]
+fn test_round_record() {
+    let shoe = ShoeBuilder::new().decks(6).seed(42).build();
+    let mut table = Table::with_shoe(shoe, 2, 0.75);
+    for _ in 0..5 {
+        play_round(&mut table);
+    }
+
+    assert_eq!(table.history().len(), 5);
+    assert!(table.player_bet(0).is_empty());
+
+    let record = table.history().round(3).unwrap();
+    assert_eq!(record.round, 3);
+    assert!(record.cards_remaining < 312);
+    assert_eq!(record.seats.len(), 2);
+    assert_eq!(record.seats[0].wager, 25);
+    assert_eq!(record.seats[1].wager, 5);
+
+    let dealt: Vec<_> = record
+        .steps
+        .iter()
+        .filter_map(|s| match s {
+            Step::Card { seat, card } => Some((*seat, *card)),
+            _ => None,
+        })
+        .collect();
+    let dealer: Vec<_> = dealt
+        .iter()
+        .filter(|(seat, _)| seat.is_none())
+        .map(|(_, c)| *c)
+        .collect();
+    assert_eq!(dealer, record.dealer);
+    for (i, seat) in record.seats.iter().enumerate() {
+        let cards: Vec<_> = dealt
+            .iter()
+            .filter(|(s, _)| *s == Some(i))
+            .map(|(_, c)| *c)
+            .collect();
+        assert_eq!(cards, seat.cards);
+    }
+
+    for record in table.history().iter() {
+        for seat in &record.seats {
+            let expected = match seat.outcome {
+                Outcome::Blackjack => seat.wager as f32 * 1.5,
+                Outcome::Win => seat.wager as f32,
+                Outcome::Lose => -(seat.wager as f32),
+                Outcome::Push => 0.0,
+            };
+            assert_eq!(seat.payout, expected);
+        }
+    }
+}
+
+#[test]
+fn test_history_capacity() {
+    let mut history = History::with_capacity(2);
+    for round in 1..=3 {
+        history.push(RoundRecord {
+            round,
+            ..Default::default()
+        });
+    }
+    assert_eq!(history.len(), 2);
+    assert!(history.round(1).is_none());
+    assert_eq!(history.round(3).unwrap().round, 3);
+    assert_eq!(history.last().unwrap().round, 3);
+
+    history.set_capacity(0);
+    assert!(history.is_empty());
+}
+
+#[test]
+fn test_history_round_gaps() {
+    let mut history = History::default();
+    for round in [2, 3, 7, 10] {
+        history.push(RoundRecord {
+            round,
+            ..Default::default()
+        });
+    }
+    assert_eq!(history.round(7).unwrap().round, 7);
+    assert_eq!(history.round(10).unwrap().round, 10);
+    assert!(history.round(4).is_none());
+    assert!(history.round(1).is_none());
+}
+
+#[test]
+fn test_text_round_trip() {
+    let shoe = ShoeBuilder::new().decks(2).seed(9).build();
+    let mut table = Table::with_shoe(shoe, 2, 0.75);
+    for _ in 0..8 {
+        play_round(&mut table);
+    }
+
+    let text = to_text(table.history().iter());
+    assert!(text.starts_with("Round #1\n"));
+    let parsed = from_text(&text).unwrap();
+    let records: Vec<RoundRecord> = table.history().iter().cloned().collect();
+    assert_eq!(parsed, records);
+
+    let last = table.history().last().unwrap();
+    let single: RoundRecord = last.to_string().parse().unwrap();
+    assert_eq!(&single, last);
+    assert_eq!(
+        single.seats[0].hand().value(),
+        table.history().last().unwrap().seats[0].hand().value()
+    );
+}
+
+#[test]
+fn test_text_parse_errors() {
+    let text = "Round #1\nShoe: 52 cards, penetration 0, running count 0, true count 0\nSeat 1: wager 5\n*** PLAY ***\nSeat 1: Xx\n";
+    let err = from_text(text).unwrap_err();
+    assert_eq!(err.line, 5);
+    assert!(err.to_string().contains("Xx"));
+
+    let err = from_text("Round #one").unwrap_err();
+    assert_eq!(err.line, 1);
+    assert!("Round #1".parse::<RoundRecord>().is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_round_trip() {
+    use shoo::history::{from_json, to_json};
+
+    let mut table = Table::new(1, 1, 0.5);
+    for _ in 0..3 {
+        play_round_single(&mut table);
+    }
+    let json = to_json(table.history().iter());
+    let records: Vec<RoundRecord> = table.history().iter().cloned().collect();
+    assert_eq!(from_json(&json).unwrap(), records);
+}
+
+#[cfg(feature = "json")]
+fn play_round_single(table: &mut Table) {
+    table.place_bet(0, Bet::from(Chip::Five));
+    table.deal();
+    table.player_stand(0);
+    table.flip_hole();
+    table.clear_hands();
+}
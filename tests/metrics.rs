@@ -0,0 +1,67 @@
+use shoo::{betting::TrueCountRamp, metrics::Metrics, sim::Simulator, table::TableRules};
+
+#[test]
+fn test_metrics() {
+    // Winning 1% of a unit per round with a standard deviation of 1.15 units
+    let metrics = Metrics::new(0.01, 1.15).hands_per_hour(80.0);
+    let di = metrics.di().unwrap();
+    assert!((di - 8.696).abs() < 1e-3);
+    assert!((metrics.score().unwrap() - di.powi(2)).abs() < 1e-6);
+    assert!((metrics.n0().unwrap() - 13_225.0).abs() < 1e-6);
+    assert!((metrics.hours_to_n0().unwrap() - 165.3125).abs() < 1e-6);
+    assert!((metrics.win_per_hour() - 0.8).abs() < 1e-9);
+    assert!((metrics.std_dev_per_hour() - 1.15 * 80f64.sqrt()).abs() < 1e-9);
+
+    let bankroll = metrics.bankroll_for_risk(0.05);
+    assert!((metrics.risk_of_ruin(bankroll) - 0.05).abs() < 1e-9);
+    assert!(metrics.risk_of_ruin(2.0 * bankroll) < 0.05);
+}
+
+#[test]
+fn test_from_stats() {
+    let stats = Simulator::new(TableRules::default())
+        .seed(4)
+        .play_rounds(5_000);
+    let metrics = Metrics::from(&stats);
+    assert_eq!(metrics.ev(), stats.ev());
+    assert_eq!(metrics.std_dev(), stats.std_dev());
+    assert_eq!(metrics.win_per_hour(), 100.0 * stats.ev());
+}
+
+#[test]
+fn test_no_edge() {
+    let metrics = Metrics::new(-0.005, 1.15);
+    assert_eq!(metrics.risk_of_ruin(1_000.0), 1.0);
+    assert_eq!(metrics.bankroll_for_risk(0.01), f64::INFINITY);
+    assert!(metrics.di().unwrap() < 0.0);
+}
+
+#[test]
+fn test_degenerate() {
+    let even = Metrics::new(0.0, 1.15);
+    assert_eq!(even.n0(), None);
+    assert_eq!(even.hours_to_n0(), None);
+    assert_eq!(even.di(), Some(0.0));
+
+    let certain = Metrics::new(0.01, 0.0);
+    assert_eq!(certain.di(), None);
+    assert_eq!(certain.score(), None);
+    assert_eq!(certain.n0(), Some(0.0));
+}
+
+#[test]
+fn test_simulate() {
+    let rules = TableRules {
+        penetration: 0.8,
+        ..TableRules::default()
+    };
+    let configured = || {
+        Simulator::new(rules)
+            .seed(9)
+            .with_betting(TrueCountRamp::new(1).step(2.0, 4).step(4.0, 8))
+    };
+    let metrics = Metrics::simulate(&mut configured(), 5_000);
+    let stats = configured().play_rounds(5_000);
+    assert_eq!(metrics, Metrics::from_stats(&stats));
+    assert!(stats.wagered() > 5_000.0);
+}
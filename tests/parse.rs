@@ -0,0 +1,66 @@
+use shoo::{Card, Hand, ParseCardError, Rank, Suit};
+
+#[test]
+fn test_parse_card() {
+    let ace = Card::new(Suit::Spade, Rank::Ace);
+    for s in ["As", "as", "AS", "A♠", "A♤", " As "] {
+        assert_eq!(s.parse::<Card>(), Ok(ace), "{s}");
+    }
+    let ten = Card::new(Suit::Heart, Rank::Ten);
+    assert_eq!("10h".parse(), Ok(ten));
+    assert_eq!("Th".parse(), Ok(ten));
+    assert_eq!("KD".parse(), Ok(Card::new(Suit::Diamond, Rank::King)));
+    assert_eq!("q♣".parse(), Ok(Card::new(Suit::Club, Rank::Queen)));
+}
+
+#[test]
+fn test_card_display_round_trip() {
+    for suit in [Suit::Heart, Suit::Diamond, Suit::Club, Suit::Spade] {
+        assert_eq!(suit.to_string().parse(), Ok(suit));
+        for rank in [Rank::Ace, Rank::Five, Rank::Ten, Rank::Jack, Rank::King] {
+            assert_eq!(rank.to_string().parse(), Ok(rank));
+            let card = Card::new(suit, rank);
+            assert_eq!(card.to_string().parse(), Ok(card));
+        }
+    }
+}
+
+#[test]
+fn test_parse_card_errors() {
+    assert_eq!("".parse::<Card>(), Err(ParseCardError::Empty));
+    assert_eq!(
+        "A".parse::<Card>(),
+        Err(ParseCardError::Incomplete("A".to_string()))
+    );
+    assert_eq!(
+        "1s".parse::<Card>(),
+        Err(ParseCardError::InvalidRank("1".to_string()))
+    );
+    let err = "Ax".parse::<Card>().unwrap_err();
+    assert_eq!(err, ParseCardError::InvalidSuit("x".to_string()));
+    assert!(err.to_string().contains("`x`"));
+}
+
+#[test]
+fn test_parse_hand() {
+    let hand: Hand = "As Kh".parse().unwrap();
+    assert!(hand.blackjack());
+    assert_eq!(
+        hand.cards(),
+        [
+            Card::new(Suit::Spade, Rank::Ace),
+            Card::new(Suit::Heart, Rank::King)
+        ]
+    );
+
+    let hand: Hand = "5c, 6d,10s".parse().unwrap();
+    assert_eq!(hand.value(), Some(21));
+    let parsed: Hand = hand.to_string().parse().unwrap();
+    assert_eq!(parsed.cards(), hand.cards());
+
+    assert!("".parse::<Hand>().unwrap().is_empty());
+    assert_eq!(
+        "As Kz".parse::<Hand>().unwrap_err(),
+        ParseCardError::InvalidSuit("z".to_string())
+    );
+}
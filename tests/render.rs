@@ -0,0 +1,54 @@
+use shoo::{
+    Card, Hand, Rank, Suit,
+    render::{CardStyle, Renderer},
+    table::Table,
+};
+
+#[test]
+fn test_card_styles() {
+    let card = Card::new(Suit::Spade, Rank::Ace);
+    let ten = Card::new(Suit::Heart, Rank::Ten);
+    assert_eq!(Renderer::new(CardStyle::Ascii).card(card), "As");
+    assert_eq!(Renderer::new(CardStyle::Ascii).card(ten), "Th");
+    assert_eq!(Renderer::default().card(card), card.to_string());
+    assert_eq!(Renderer::new(CardStyle::Filled).card(ten), "10♥");
+    assert_eq!(Renderer::new(CardStyle::Unicode).card(card), "\u{1F0A1}");
+    assert_eq!(
+        Renderer::new(CardStyle::Unicode).card(Card::new(Suit::Club, Rank::Queen)),
+        "\u{1F0DD}"
+    );
+    assert_eq!(
+        Renderer::new(CardStyle::AsciiArt).card(ten),
+        "+-----+\n|10   |\n|  h  |\n|   10|\n+-----+"
+    );
+}
+
+#[test]
+fn test_colored() {
+    let renderer = Renderer::new(CardStyle::Ascii).colored(true);
+    assert_eq!(
+        renderer.card(Card::new(Suit::Diamond, Rank::Two)),
+        "\x1b[31m2d\x1b[0m"
+    );
+    assert_eq!(renderer.card(Card::new(Suit::Club, Rank::Two)), "2c");
+}
+
+#[test]
+fn test_hand_and_table() {
+    let hand: Hand = "As Kh".parse().unwrap();
+    assert_eq!(Renderer::default().hand(&hand), hand.to_string());
+    assert!(
+        Renderer::new(CardStyle::Ascii)
+            .hand(&hand)
+            .starts_with("[As, Kh] ")
+    );
+    let art = Renderer::new(CardStyle::AsciiArt).hand(&hand);
+    assert_eq!(art.lines().count(), 5);
+    assert!(art.starts_with("+-----+ +-----+"));
+
+    let mut table = Table::new(1, 2, 0.5);
+    table.deal();
+    assert_eq!(Renderer::default().table(&table), table.to_string());
+    let art = Renderer::new(CardStyle::AsciiArt).table(&table);
+    assert_eq!(art.matches("+-----+ +-----+").count(), 6);
+}
@@ -0,0 +1,145 @@
+use shoo::{
+    Bet, Card, ShoeBuilder,
+    history::RoundRecord,
+    replay::{Move, ReplayError, Replayer},
+    table::{Action, Table},
+};
+
+/// Play a few rounds where seat 1 always stands and seat 2 hits below 17
+fn session() -> (Vec<RoundRecord>, Vec<Card>) {
+    let shoe = ShoeBuilder::new().decks(2).seed(21).build();
+    let order: Vec<Card> = shoe.upcoming().collect();
+    let mut table = Table::with_shoe(shoe, 2, 0.9);
+    for _ in 0..6 {
+        table.place_bet(0, Bet::from_units(10));
+        table.place_bet(1, Bet::from_units(15));
+        table.deal();
+        if !table.peek() {
+            table.player_stand(0);
+            while table.player_hand(1).value().is_some_and(|v| v < 17) {
+                table.player_hit(1);
+            }
+            if !table.player_hand(1).busted() {
+                table.player_stand(1);
+            }
+            table.flip_hole();
+            while table.dealer_value().is_some_and(|v| v < 17) {
+                table.dealer_hit();
+            }
+        }
+        table.clear_hands();
+    }
+    (table.history().iter().cloned().collect(), order)
+}
+
+#[test]
+fn test_replay_forward_backward() {
+    let (records, order) = session();
+    let mut replayer = Replayer::new(records.clone(), order).unwrap();
+    assert_eq!(replayer.next_move(), Some(Move::Deal { round: 1 }));
+
+    while replayer.forward().is_some() {}
+    assert_eq!(replayer.position(), replayer.len());
+    let replayed: Vec<RoundRecord> = replayer.table().history().iter().cloned().collect();
+    assert_eq!(replayed, records);
+
+    assert!(replayer.backward());
+    assert_eq!(replayer.next_move(), Some(Move::Settle));
+    replayer.seek(1);
+    assert_eq!(
+        replayer.table().player_hand(0).cards(),
+        &records[0].seats[0].cards[..2]
+    );
+    replayer.seek(0);
+    assert!(!replayer.backward());
+    assert!(replayer.table().history().is_empty());
+}
+
+#[test]
+fn test_replay_decisions() {
+    let (records, _) = session();
+    let actions = records
+        .iter()
+        .flat_map(|r| &r.steps)
+        .filter(|s| matches!(s, shoo::history::Step::Action { .. }))
+        .count();
+
+    let mut replayer = Replayer::from_records(records).unwrap();
+    let decisions = replayer.decisions();
+    assert_eq!(decisions.len(), actions);
+    for decision in &decisions {
+        if decision.seat == 0 {
+            assert_eq!(decision.taken, Action::Stand);
+        }
+        assert_eq!(
+            decision.is_mistake(),
+            decision.taken != decision.recommended
+        );
+        assert!(Table::SUPPORTED_ACTIONS.contains(&decision.recommended));
+    }
+    assert!(replayer.mistakes().iter().all(|d| d.is_mistake()));
+
+    replayer.forward();
+    let first = replayer.decision().unwrap();
+    assert_eq!(first.round, 1);
+    assert_eq!(first.hand.cards(), decisions[0].hand.cards());
+    assert_eq!(first.true_count, decisions[0].true_count);
+}
+
+#[test]
+fn test_replay_counts_across_reshuffle() {
+    // Record the counts of the real shoe at every decision, over a reshuffle
+    let shoe = ShoeBuilder::new().decks(1).seed(3).build();
+    let mut table = Table::with_shoe(shoe, 1, 0.5);
+    let (mut counts, mut reshuffled) = (Vec::new(), false);
+    for _ in 0..12 {
+        table.place_bet(0, Bet::from_units(10));
+        reshuffled |= table.deal();
+        if !table.peek() {
+            loop {
+                counts.push((table.shoe().running_count(), table.shoe().true_count()));
+                if table.player_hand(0).value().is_some_and(|v| v < 15) {
+                    table.player_hit(0);
+                } else {
+                    table.player_stand(0);
+                    break;
+                }
+            }
+            table.flip_hole();
+        }
+        table.clear_hands();
+    }
+    assert!(reshuffled);
+
+    let records: Vec<RoundRecord> = table.history().iter().cloned().collect();
+    let decisions = Replayer::from_records(records).unwrap().decisions();
+    assert_eq!(decisions.len(), counts.len());
+    for (decision, (running, true_count)) in decisions.iter().zip(counts) {
+        assert!((decision.running_count - running).abs() < 1e-4);
+        assert!((decision.true_count - true_count).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_allowed_actions() {
+    // A pair of 8s against a 6, which the rules would let the player split or surrender
+    let cards = ["8h", "6d", "8c", "Td"].map(|c| c.parse::<Card>().unwrap());
+    let shoe = ShoeBuilder::new().cards(cards).stacked().build();
+    let mut table = Table::with_shoe(shoe, 1, 0.9);
+    assert!(table.rules().surrender && table.rules().max_splits > 0);
+    table.place_bet(0, Bet::from_units(10));
+    table.deal();
+    assert_eq!(table.allowed_actions(0), [Action::Stand, Action::Hit]);
+}
+
+#[test]
+fn test_replay_mismatched_order() {
+    let (records, mut order) = session();
+    order.swap(0, 1);
+    if order[0] != order[1] {
+        assert_eq!(
+            Replayer::new(records, order).err(),
+            Some(ReplayError::MismatchedOrder { round: 1 })
+        );
+    }
+}
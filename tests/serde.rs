@@ -0,0 +1,83 @@
+#![cfg(feature = "json")]
+
+use shoo::{
+    Bet, Card, Chip, Hand, Rank, ShoeBuilder, Suit,
+    strategy::{BasicStrategy, Chart, ChartEntry},
+    table::Table,
+};
+
+fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn test_cards_and_bets() {
+    let card = Card::new(Suit::Spade, Rank::Ace);
+    assert_eq!(round_trip(&card), card);
+
+    let mut hand = Hand::default();
+    hand.insert(card);
+    hand.insert(Card::new(Suit::Heart, Rank::King));
+    let hand = round_trip(&hand);
+    assert!(hand.blackjack());
+
+    let bet = Bet::from(Chip::TwentyFive) + Bet::from(Chip::One);
+    assert_eq!(round_trip(&bet), bet);
+}
+
+#[test]
+fn test_chart() {
+    let chart = BasicStrategy::default().chart().clone();
+    assert_eq!(round_trip(&chart), chart);
+    assert!(serde_json::from_str::<Chart>(r#"{"Hard99": []}"#).is_err());
+
+    let mut chart = chart;
+    chart.set_cards([Rank::Ten, Rank::Two], [ChartEntry::Hit; 10]);
+    let json = serde_json::to_string(&chart).unwrap();
+    assert!(json.contains(r#""10-2":"#), "{json}");
+    assert_eq!(serde_json::from_str::<Chart>(&json).unwrap(), chart);
+}
+
+#[test]
+fn test_resume_mid_round() {
+    let shoe = ShoeBuilder::new().decks(2).seed(3).build();
+    let mut table = Table::with_shoe(shoe, 2, 0.5);
+    table.set_training(true);
+    table.place_bet(0, Bet::from_units(10));
+    table.place_bet(1, Bet::from_units(5));
+    table.deal();
+    table.player_hit(0);
+
+    let json = serde_json::to_string(&table).unwrap();
+    let mut resumed: Table = serde_json::from_str(&json).unwrap();
+    assert_eq!(resumed.to_string(), table.to_string());
+    assert!(resumed.undo());
+    assert!(table.undo());
+
+    for table in [&mut table, &mut resumed] {
+        table.player_stand(0);
+        table.player_stand(1);
+        table.flip_hole();
+        while table.dealer_value().is_some_and(|v| v < 17) {
+            table.dealer_hit();
+        }
+        table.clear_hands();
+        // Play past the cut card so the resumed shoe shuffles too
+        for _ in 0..20 {
+            table.place_bet(0, Bet::from_units(5));
+            table.place_bet(1, Bet::from_units(5));
+            table.deal();
+            table.player_stand(0);
+            table.player_stand(1);
+            table.flip_hole();
+            table.clear_hands();
+        }
+    }
+    let played: Vec<_> = table.history().iter().cloned().collect();
+    let replayed: Vec<_> = resumed.history().iter().cloned().collect();
+    assert_eq!(played, replayed);
+    assert_eq!(
+        resumed.shoe().upcoming().collect::<Vec<_>>(),
+        table.shoe().upcoming().collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,63 @@
+use shoo::{Card, Rank, ShoeBuilder, Suit};
+
+#[test]
+fn test_spanish_shoe() {
+    let mut shoe = ShoeBuilder::new().spanish_decks(6).build();
+    assert_eq!(shoe.len(), 6 * 48);
+    assert_eq!(shoe.num_decks(), 6);
+    while let Some(card) = shoe.deal() {
+        assert_ne!(card.rank(), Rank::Ten);
+    }
+}
+
+#[test]
+fn test_custom_shoe() {
+    let shoe = ShoeBuilder::new()
+        .decks(1)
+        .remove_rank(Rank::Five)
+        .add_rank(Rank::Ace, 4)
+        .remove_card(Card::new(Suit::Spade, Rank::King), 1)
+        .build();
+    assert_eq!(shoe.len(), 52 - 4 + 4 - 1);
+    let cards: Vec<Card> = (0..shoe.len()).map(|i| shoe[i]).collect();
+    assert_eq!(cards.iter().filter(|c| c.rank() == Rank::Five).count(), 0);
+    assert_eq!(cards.iter().filter(|c| c.is_ace()).count(), 8);
+    assert!(!cards.contains(&Card::new(Suit::Spade, Rank::King)));
+}
+
+#[test]
+fn test_partial_shoe_penetration() {
+    let mut shoe = ShoeBuilder::new()
+        .partial_decks(1, &[Suit::Heart, Suit::Spade])
+        .build();
+    assert_eq!(shoe.capacity(), 26);
+    for _ in 0..13 {
+        shoe.deal();
+    }
+    assert_eq!(shoe.penetration(), 0.5);
+    shoe.reset();
+    assert_eq!(shoe.len(), 26);
+    assert_eq!(shoe.penetration(), 0.0);
+}
+
+#[test]
+fn test_remaining_and_seen() {
+    let mut shoe = ShoeBuilder::new().decks(2).build();
+    assert_eq!(shoe.remaining(Rank::Ace), 8);
+    assert!(shoe.seen().is_empty());
+
+    let dealt: Vec<Card> = (0..10).map(|_| shoe.deal().unwrap()).collect();
+    assert_eq!(shoe.seen(), dealt.as_slice());
+
+    let remaining = shoe.remaining_ranks();
+    assert_eq!(remaining.len(), 13);
+    assert_eq!(remaining.values().sum::<usize>(), 2 * 52 - 10);
+    for (&rank, &count) in &remaining {
+        let dealt = dealt.iter().filter(|c| c.rank() == rank).count();
+        assert_eq!(count + dealt, 8);
+        assert_eq!(shoe.remaining(rank), count);
+    }
+
+    shoe.reset();
+    assert!(shoe.seen().is_empty());
+}
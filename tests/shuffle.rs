@@ -0,0 +1,81 @@
+use shoo::{
+    Card, ShoeBuilder,
+    shuffle::{Cut, Plug, Riffle, ShuffleProcedure, StripCut, Wash, ZoneShuffle},
+};
+
+fn sorted(mut cards: Vec<Card>) -> Vec<Card> {
+    cards.sort();
+    cards
+}
+
+fn deal_all(shoe: &mut shoo::Shoe) -> Vec<Card> {
+    std::iter::from_fn(|| shoe.deal()).collect()
+}
+
+#[test]
+fn test_seeded_shoe() {
+    let mut a = ShoeBuilder::new().decks(2).seed(7).build();
+    let mut b = ShoeBuilder::new().decks(2).seed(7).build();
+    assert_eq!(deal_all(&mut a), deal_all(&mut b));
+}
+
+#[test]
+fn test_procedures_preserve_cards() {
+    let mut rng = rand::rng();
+    let pack: Vec<usize> = (0..312).collect();
+
+    let mut riffled = pack.clone();
+    Riffle.apply(&mut riffled, &mut rng);
+    let mut stripped = pack.clone();
+    StripCut { strips: 6 }.apply(&mut stripped, &mut rng);
+    let mut plugged = pack.clone();
+    Plug { packets: 8 }.apply(&mut plugged, &mut rng);
+    let mut zoned = pack.clone();
+    ZoneShuffle {
+        zones: 4,
+        procedure: Riffle.repeat(2).then(StripCut { strips: 4 }),
+    }
+    .apply(&mut zoned, &mut rng);
+
+    for mut shuffled in [riffled, stripped, plugged, zoned] {
+        shuffled.sort();
+        assert_eq!(shuffled, pack);
+    }
+}
+
+#[test]
+fn test_riffle_preserves_halves() {
+    let mut rng = rand::rng();
+    let mut pack: Vec<usize> = (0..52).collect();
+    Riffle.apply(&mut pack, &mut rng);
+
+    // A single riffle leaves at most two rising sequences
+    let mut position = vec![0; pack.len()];
+    for (i, &card) in pack.iter().enumerate() {
+        position[card] = i;
+    }
+    let descents = position.windows(2).filter(|w| w[0] > w[1]).count();
+    assert!(descents <= 1);
+}
+
+#[test]
+fn test_shuffle_with_discards() {
+    let mut shoe = ShoeBuilder::new().decks(1).seed(3).build();
+    let full = sorted(deal_all(&mut ShoeBuilder::new().decks(1).build()));
+    let dealt: Vec<Card> = (0..20).map(|_| shoe.deal().unwrap()).collect();
+    let stub: Vec<Card> = (0..shoe.len()).rev().map(|i| shoe[i]).collect();
+
+    // Doing nothing leaves the stub on top of the discards
+    shoe.shuffle_with(&Cut.repeat(0));
+    assert_eq!(shoe.len(), 52);
+    assert!(shoe.seen().is_empty());
+    let pack = deal_all(&mut shoe);
+    assert_eq!(&pack[..32], stub.as_slice());
+    assert_eq!(
+        pack[32..].to_vec(),
+        dealt.iter().rev().copied().collect::<Vec<_>>()
+    );
+
+    shoe.shuffle_with(&Riffle.repeat(3).then(Wash));
+    assert_eq!(sorted(deal_all(&mut shoe)), full);
+}
@@ -0,0 +1,218 @@
+use shoo::{
+    Card, Hand, Rank, ShoeBuilder, Suit,
+    betting::Flat,
+    sim::{SHARD_ROUNDS, SimStats, Simulator},
+    table::{Outcome, Table, TableRules},
+};
+
+#[test]
+fn test_basic_strategy_house_edge() {
+    let mut sim = Simulator::new(TableRules::default()).seed(1);
+    let stats = sim.play_rounds(100_000);
+    assert_eq!(stats.rounds(), 100_000);
+    assert!(stats.hands() > stats.rounds());
+
+    // Basic strategy loses about a third of a percent under these rules
+    let (low, high) = stats.confidence_interval(3.0);
+    assert!(low < -0.0035 && high > -0.0035, "{low} {high}");
+    assert!((1.0..1.3).contains(&stats.std_dev()), "{}", stats.std_dev());
+    assert!((stats.blackjack_rate() - 0.045).abs() < 0.005);
+
+    let total = stats.win_rate() + stats.loss_rate() + stats.push_rate() + stats.blackjack_rate();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert!((stats.ev() * stats.rounds() as f64 - stats.net()).abs() < 1e-6);
+}
+
+#[test]
+fn test_reproducible() {
+    let play = || {
+        Simulator::new(TableRules::default())
+            .seed(9)
+            .play_rounds(2_000)
+    };
+    assert_eq!(play(), play());
+}
+
+#[test]
+fn test_play_shoes_and_betting() {
+    let rules = TableRules {
+        decks: 2,
+        penetration: 0.5,
+        ..Default::default()
+    };
+    let mut sim = Simulator::new(rules).seed(3).with_betting(Flat::new(5));
+    let stats = sim.play_shoes(3);
+    // About 52 cards per shoe, at a little over 5 cards per round
+    assert!((15..40).contains(&stats.rounds()), "{}", stats.rounds());
+    assert_eq!(stats.wagered(), 5.0 * stats.rounds() as f64);
+    assert_eq!(sim.current_bankroll(), 1000.0 + stats.net());
+
+    let mut sat_out = Simulator::new(rules).with_betting(Flat::new(0));
+    let stats = sat_out.play_rounds(10);
+    assert_eq!(stats.rounds(), 0);
+    assert_eq!(stats.observed(), 10);
+    assert_eq!(stats.play_rate(), 0.0);
+}
+
+#[test]
+fn test_seed_keeps_shoe() {
+    let spanish = ShoeBuilder::new().spanish_decks(2).build();
+    let sim = Simulator::new(TableRules::default())
+        .with_shoe(spanish)
+        .seed(1);
+    assert_eq!(sim.shoe().capacity(), 96);
+    assert_eq!(sim.shoe().remaining(Rank::Ten), 0);
+}
+
+#[test]
+fn test_play_shoes_full_penetration() {
+    let rules = TableRules {
+        decks: 1,
+        penetration: 1.0,
+        ..Default::default()
+    };
+    // Every shoe is dealt out in the middle of a round, at a little over 5 cards per round
+    let stats = Simulator::new(rules).seed(5).play_shoes(2);
+    assert!((12..25).contains(&stats.rounds()), "{}", stats.rounds());
+}
+
+#[test]
+fn test_wonging() {
+    let flat = Simulator::new(TableRules::default())
+        .seed(5)
+        .play_rounds(50_000);
+    assert_eq!(flat.observed(), 0);
+    assert_eq!(flat.play_rate(), 1.0);
+
+    let mut sim = Simulator::new(TableRules::default())
+        .seed(5)
+        .wonging(1.0, 0.0);
+    let wonged = sim.play_rounds(50_000);
+    assert_eq!(wonged.rounds() + wonged.observed(), 50_000);
+    assert!(wonged.rounds() > 0 && wonged.observed() > wonged.rounds());
+    assert!(wonged.ev() > flat.ev(), "{} {}", wonged.ev(), flat.ev());
+    assert_eq!(sim.current_bankroll(), 1000.0 + wonged.net());
+
+    let sharded = Simulator::new(TableRules::default())
+        .wonging(1.0, 0.0)
+        .play_rounds_sharded(1_000, 5);
+    assert_eq!(sharded.rounds() + sharded.observed(), 1_000);
+}
+
+#[test]
+fn test_table_rules() {
+    let rules = TableRules {
+        decks: 2,
+        blackjack_payout: 1.2,
+        penetration: 1.5,
+        ..Default::default()
+    };
+    let table = Table::with_rules(rules, 3);
+    assert_eq!(table.shoe().num_decks(), 2);
+    assert_eq!(table.rules().blackjack_payout, 1.2);
+    assert_eq!(table.rules().penetration, 1.0);
+    assert_eq!(table.player_hands().count(), 3);
+}
+
+#[test]
+fn test_merge() {
+    let mut sim = Simulator::new(TableRules::default()).seed(2);
+    let (first, second) = (sim.play_rounds(3_000), sim.play_rounds(5_000));
+    let mut merged = first;
+    merged.merge(&second);
+
+    let all = Simulator::new(TableRules::default())
+        .seed(2)
+        .play_rounds(8_000);
+    assert_eq!(merged.rounds(), all.rounds());
+    assert_eq!(merged.hands(), all.hands());
+    assert_eq!(merged.blackjack_rate(), all.blackjack_rate());
+    assert!((merged.ev() - all.ev()).abs() < 1e-12);
+    assert!((merged.variance() - all.variance()).abs() < 1e-9);
+
+    merged.merge(&SimStats::new());
+    assert_eq!(merged.rounds(), all.rounds());
+}
+
+#[test]
+fn test_sharded() {
+    let sim = Simulator::new(TableRules::default());
+    let rounds = 2 * SHARD_ROUNDS + 10;
+    let stats = sim.play_rounds_sharded(rounds, 7);
+    assert_eq!(stats.rounds(), rounds);
+    assert_eq!(
+        sim.play_rounds_sharded(20, 7),
+        sim.play_rounds_sharded(20, 7)
+    );
+    assert_ne!(
+        sim.play_rounds_sharded(20, 7),
+        sim.play_rounds_sharded(20, 8)
+    );
+
+    #[cfg(feature = "rayon")]
+    {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let single =
+            pool.install(|| Simulator::new(TableRules::default()).play_rounds_sharded(rounds, 7));
+        assert_eq!(single, stats);
+    }
+}
+
+#[test]
+fn test_shared_round_rules() {
+    let hand = |text: &str| text.parse::<Hand>().unwrap();
+    let (twenty_one, twenty) = (hand("Ah Kc"), hand("10d Qs"));
+    assert_eq!(Outcome::of(&twenty_one, true, &twenty), Outcome::Blackjack);
+    assert_eq!(Outcome::of(&twenty_one, false, &twenty), Outcome::Win);
+    assert_eq!(
+        Outcome::of(&twenty_one, true, &hand("As Jd")),
+        Outcome::Push
+    );
+    assert_eq!(
+        Outcome::of(&hand("9h 2c Kd"), true, &hand("As Jd")),
+        Outcome::Lose
+    );
+
+    let rules = TableRules::default();
+    assert_eq!(rules.payout(Outcome::Blackjack, 2.0), 3.0);
+    assert_eq!(rules.payout(Outcome::Lose, 2.0), -2.0);
+    let soft17 = hand("Ah 6c");
+    assert!(!rules.dealer_hits(&soft17));
+    assert!(!rules.dealer_hits(&hand("10h 7c")));
+    assert!(rules.dealer_hits(&hand("10h 6c")));
+    let h17 = TableRules {
+        dealer_hits_soft_17: true,
+        ..rules
+    };
+    assert!(h17.dealer_hits(&soft17));
+    assert!(!h17.dealer_hits(&hand("10h 7c")));
+
+    let mut table = Table::with_rules(h17, 1);
+    table.deal();
+    table.player_stand(0);
+    table.dealer_play();
+    assert!(!h17.dealer_hits(table.dealer_hand()));
+}
+
+#[test]
+fn test_no_peek_surrender() {
+    // Hard 16 surrenders against an ace
+    let round = |hole: Rank, peek: bool| {
+        let cards = [Rank::Ten, Rank::Ace, Rank::Six, hole];
+        let shoe = ShoeBuilder::new()
+            .cards(cards.map(|rank| Card::new(Suit::Spade, rank)))
+            .stacked()
+            .build();
+        let rules = TableRules {
+            peek,
+            ..Default::default()
+        };
+        Simulator::new(rules).with_shoe(shoe).play_rounds(1).net()
+    };
+    assert_eq!(round(Rank::Nine, false), -0.5);
+    assert_eq!(round(Rank::King, false), -1.0);
+    assert_eq!(round(Rank::King, true), -1.0);
+}
@@ -0,0 +1,133 @@
+use shoo::{
+    Card, Hand, Rank, Suit,
+    strategy::{BasicStrategy, Chart, ChartEntry, ChartRow, PlayingStrategy},
+    table::Action,
+};
+
+fn hand(ranks: &[Rank]) -> Hand {
+    let mut hand = Hand::default();
+    for &rank in ranks {
+        hand.insert(Card::new(Suit::Club, rank));
+    }
+    hand
+}
+
+fn upcard(rank: Rank) -> Card {
+    Card::new(Suit::Heart, rank)
+}
+
+const ALL: [Action; 5] = [
+    Action::Hit,
+    Action::Stand,
+    Action::Double,
+    Action::Split,
+    Action::Surrender,
+];
+
+#[test]
+fn test_basic_strategy() {
+    let strategy = BasicStrategy::default();
+    let hard16 = hand(&[Rank::Ten, Rank::Six]);
+    assert_eq!(
+        strategy.action(&hard16, upcard(Rank::King), &ALL),
+        Action::Surrender
+    );
+    assert_eq!(
+        strategy.action(&hard16, upcard(Rank::King), &[Action::Hit, Action::Stand]),
+        Action::Hit
+    );
+    assert_eq!(
+        strategy.action(&hard16, upcard(Rank::Six), &ALL),
+        Action::Stand
+    );
+
+    let soft18 = hand(&[Rank::Ace, Rank::Seven]);
+    assert_eq!(
+        strategy.action(&soft18, upcard(Rank::Three), &ALL),
+        Action::Double
+    );
+    assert_eq!(
+        strategy.action(&soft18, upcard(Rank::Three), &[Action::Hit, Action::Stand]),
+        Action::Stand
+    );
+
+    let eights = hand(&[Rank::Eight, Rank::Eight]);
+    assert_eq!(
+        strategy.action(&eights, upcard(Rank::Ace), &ALL),
+        Action::Split
+    );
+    // Without splitting, 8-8 is played as hard 16
+    assert_eq!(
+        strategy.action(&eights, upcard(Rank::Seven), &[Action::Hit, Action::Stand]),
+        Action::Hit
+    );
+
+    let face_pair = hand(&[Rank::King, Rank::Jack]);
+    assert_eq!(
+        strategy.action(&face_pair, upcard(Rank::Six), &ALL),
+        Action::Stand
+    );
+}
+
+#[test]
+fn test_chart_round_trip() {
+    let chart = BasicStrategy::default().chart().clone();
+    assert_eq!(chart.rows().count(), 18 + 10 + 10);
+    let text = chart.to_string();
+    assert_eq!(text.parse::<Chart>().unwrap(), chart);
+    assert_eq!(
+        chart.get(ChartRow::Pair(Rank::Ten), upcard(Rank::Five)),
+        Some(ChartEntry::Stand)
+    );
+}
+
+#[test]
+fn test_chart_parse_errors() {
+    assert!("Hard16: S S".parse::<Chart>().is_err());
+    assert!("Hard16 S S S S S H H H H H".parse::<Chart>().is_err());
+    assert!("Pair11: S S S S S H H H H H".parse::<Chart>().is_err());
+    assert!("Hard16: S S S S S H H H H X".parse::<Chart>().is_err());
+
+    let chart: Chart = "# comment\n\nSoft13: H H H D D H H H H H\n"
+        .parse()
+        .unwrap();
+    assert_eq!(chart.rows().count(), 1);
+}
+
+#[test]
+fn test_two_card_rows() {
+    let mut chart = BasicStrategy::default().chart().clone();
+    chart.set_cards([Rank::Two, Rank::Jack], [ChartEntry::Hit; 10]);
+    assert_eq!(chart.card_rows().count(), 1);
+    assert_eq!(
+        chart.get_cards([Rank::Ten, Rank::Two], upcard(Rank::Four)),
+        Some(ChartEntry::Hit)
+    );
+    assert_eq!(
+        chart.get_cards([Rank::Nine, Rank::Three], upcard(Rank::Four)),
+        None
+    );
+
+    // Two-card rows take precedence over the total, but not over splitting or more cards
+    let twelve = hand(&[Rank::Queen, Rank::Two]);
+    assert_eq!(chart.action(&twelve, upcard(Rank::Four), &ALL), Action::Hit);
+    let nine_three = hand(&[Rank::Nine, Rank::Three]);
+    assert_eq!(
+        chart.action(&nine_three, upcard(Rank::Four), &ALL),
+        Action::Stand
+    );
+    let hard13 = hand(&[Rank::Ten, Rank::Two, Rank::Ace]);
+    assert_eq!(
+        chart.action(&hard13, upcard(Rank::Four), &ALL),
+        Action::Stand
+    );
+
+    let text = chart.to_string();
+    assert!(
+        text.contains("\n10-2:     H  H  H  H  H  H  H  H  H  H"),
+        "{text}"
+    );
+    assert_eq!(text.parse::<Chart>().unwrap(), chart);
+    assert!("10-X: H H H H H H H H H H".parse::<Chart>().is_err());
+    assert!("10+2: H H H H H H H H H H".parse::<Chart>().is_err());
+}
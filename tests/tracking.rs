@@ -0,0 +1,51 @@
+use shoo::{
+    ShoeBuilder,
+    shuffle::{Riffle, ShuffleProcedure, StripCut},
+    tracking::{SlugKind, SlugTracker},
+};
+
+#[test]
+fn test_track_unshuffled() {
+    let mut shoe = ShoeBuilder::new().decks(2).seed(11).build();
+    for _ in 0..80 {
+        shoe.deal();
+    }
+
+    let tracker = SlugTracker::new(13, 3);
+    let before = tracker.pack_segments(&shoe);
+    let identity = Riffle.repeat(0);
+    let estimate = tracker.estimate(&shoe, &identity, 4, &mut rand::rng());
+
+    let expected: Vec<f32> = before.iter().map(|s| s.count as f32).collect();
+    assert_eq!(estimate.expected_counts, expected);
+    for slug in &estimate.slugs {
+        let center = slug.segment.start as f32 + (slug.segment.len - 1) as f32 / 2.0;
+        assert!((slug.landing - center).abs() < 1e-3);
+        assert_eq!(slug.kind == SlugKind::High, slug.segment.count < 0);
+        assert!(slug.segment.count.abs() >= 3);
+    }
+
+    shoe.shuffle_with(&identity);
+    assert_eq!(tracker.shoe_segments(&shoe), before);
+}
+
+#[test]
+fn test_track_total_count() {
+    let mut shoe = ShoeBuilder::new().decks(6).seed(5).build();
+    for _ in 0..200 {
+        shoe.deal();
+    }
+
+    let tracker = SlugTracker::new(52, 4);
+    let procedure = Riffle.then(StripCut { strips: 5 }).then(Riffle);
+    let estimate = tracker.estimate(&shoe, &procedure, 50, &mut rand::rng());
+    assert_eq!(estimate.expected_counts.len(), 6);
+
+    // A full pack of balanced decks always counts to zero
+    let total: f32 = estimate.expected_counts.iter().sum();
+    assert!(total.abs() < 1e-3);
+    for slug in &estimate.slugs {
+        assert!(slug.landing >= 0.0 && slug.landing < 312.0);
+        assert!(slug.spread > 0.0);
+    }
+}
@@ -0,0 +1,116 @@
+use shoo::{
+    Bet, ShoeBuilder,
+    history::{Step, from_text, to_text},
+    replay::Replayer,
+    table::{Action, Table},
+};
+
+fn table() -> Table {
+    let shoe = ShoeBuilder::new().decks(1).seed(8).build();
+    let mut table = Table::with_shoe(shoe, 1, 0.75);
+    table.set_training(true);
+    table.place_bet(0, Bet::from_units(5));
+    table.deal();
+    table
+}
+
+#[test]
+fn test_undo_redo_hit() {
+    let mut table = table();
+    let hand = table.player_hand(0).clone();
+    let count = table.shoe().running_count();
+    let remaining = table.shoe().len();
+
+    table.player_hit(0);
+    let card = *table.player_hand(0).cards().last().unwrap();
+    assert!(table.undo());
+    assert_eq!(table.player_hand(0).cards(), hand.cards());
+    assert_eq!(table.shoe().running_count(), count);
+    assert_eq!(table.shoe().len(), remaining);
+    assert_eq!(table.shoe().upcoming().next(), Some(card));
+    assert!(!table.undo());
+
+    assert!(table.redo());
+    assert_eq!(table.player_hand(0).cards().last(), Some(&card));
+    assert!(!table.redo());
+
+    table.player_stand(0);
+    table.flip_hole();
+    assert!(!table.undo());
+    table.clear_hands();
+
+    let steps = &table.history().last().unwrap().steps;
+    let flagged: Vec<&Step> = steps
+        .iter()
+        .filter(|s| matches!(s, Step::Undo { .. } | Step::Redo { .. }))
+        .collect();
+    assert_eq!(
+        flagged,
+        [
+            &Step::Undo {
+                seat: 0,
+                action: Action::Hit
+            },
+            &Step::Redo {
+                seat: 0,
+                action: Action::Hit
+            }
+        ]
+    );
+}
+
+#[test]
+fn test_new_action_clears_redo() {
+    let mut table = table();
+    table.player_hit(0);
+    assert!(table.undo());
+    table.player_stand(0);
+    assert!(!table.redo());
+    assert!(table.undo());
+    assert!(table.redo());
+}
+
+#[test]
+fn test_undo_requires_training() {
+    let mut table = table();
+    table.set_training(false);
+    table.player_hit(0);
+    assert!(!table.undo());
+    assert!(!table.training());
+}
+
+#[test]
+fn test_replay_with_undo() {
+    let mut table = table();
+    table.player_hit(0);
+    table.undo();
+    table.player_stand(0);
+    table.flip_hole();
+    table.clear_hands();
+
+    let records: Vec<_> = table.history().iter().cloned().collect();
+    assert_eq!(from_text(&to_text(&records)).unwrap(), records);
+
+    let mut replayer = Replayer::from_records(records.clone()).unwrap();
+    while replayer.forward().is_some() {}
+    let replayed = replayer.table().history().last().unwrap();
+    assert_eq!(replayed.steps, records[0].steps);
+    assert_eq!(replayed.seats, records[0].seats);
+}
+
+#[test]
+fn test_undo_cut_card() {
+    // The cut card comes out with the first card after the deal
+    let shoe = ShoeBuilder::new().decks(1).seed(8).build();
+    let mut table = Table::with_shoe(shoe, 1, 0.08);
+    table.set_training(true);
+    table.deal();
+    assert!(!table.cut_card_reached());
+
+    table.player_hit(0);
+    assert!(table.cut_card_reached());
+    assert!(table.undo());
+    assert!(!table.cut_card_reached());
+    assert!(table.redo());
+    assert!(table.cut_card_reached());
+}